@@ -0,0 +1,216 @@
+use chrono::{Datelike, Local, NaiveDate};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::{
+  api::{client::absences::AbsenceParams, Absence, AbsenceStatus, AbsenceType},
+  config::Config,
+  output::{Message, OutputFormat, Render},
+  time::{parse_date, API_DATE_FORMAT},
+  CliError,
+};
+
+#[derive(Debug, Serialize)]
+struct AbsenceOutput {
+  id: u32,
+  users_id: u32,
+  #[serde(rename = "type")]
+  absence_type: AbsenceType,
+  status: AbsenceStatus,
+  date_since: String,
+  date_until: String,
+  count_days: Option<f64>,
+  note: Option<String>,
+}
+
+impl From<Absence> for AbsenceOutput {
+  fn from(absence: Absence) -> Self {
+    AbsenceOutput {
+      id: absence.id,
+      users_id: absence.users_id,
+      absence_type: absence.absence_type,
+      status: absence.status,
+      date_since: absence.date_since,
+      date_until: absence.date_until,
+      count_days: absence.count_days,
+      note: absence.note,
+    }
+  }
+}
+
+impl AbsenceOutput {
+  const HEADERS: [&'static str; 8] = [
+    "id",
+    "users_id",
+    "type",
+    "status",
+    "date_since",
+    "date_until",
+    "count_days",
+    "note",
+  ];
+
+  fn row(&self) -> Vec<String> {
+    vec![
+      self.id.to_string(),
+      self.users_id.to_string(),
+      self.absence_type.to_string(),
+      self.status.to_string(),
+      self.date_since.clone(),
+      self.date_until.clone(),
+      self.count_days.map(|d| d.to_string()).unwrap_or_default(),
+      self.note.clone().unwrap_or_default(),
+    ]
+  }
+}
+
+impl Render for AbsenceOutput {
+  fn print_human(&self) {
+    println!("ID:\t\t{}", self.id);
+    println!("User ID:\t{}", self.users_id);
+    println!("Type:\t\t{}", self.absence_type);
+    println!("Status:\t\t{}", self.status);
+    println!("Since:\t\t{}", self.date_since);
+    println!("Until:\t\t{}", self.date_until);
+    if let Some(count_days) = self.count_days {
+      println!("Days:\t\t{count_days}");
+    }
+    if let Some(note) = &self.note {
+      println!("Note:\t\t{note}");
+    }
+  }
+
+  fn headers(&self) -> Vec<&str> {
+    Self::HEADERS.to_vec()
+  }
+
+  fn rows(&self) -> Vec<Vec<String>> {
+    vec![self.row()]
+  }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+struct AbsencesOutput(Vec<AbsenceOutput>);
+
+impl Render for AbsencesOutput {
+  fn print_human(&self) {
+    if self.0.is_empty() {
+      println!("No absences found.");
+    }
+    for (i, absence) in self.0.iter().enumerate() {
+      if i > 0 {
+        println!();
+      }
+      absence.print_human();
+    }
+  }
+
+  fn headers(&self) -> Vec<&str> {
+    AbsenceOutput::HEADERS.to_vec()
+  }
+
+  fn rows(&self) -> Vec<Vec<String>> {
+    self.0.iter().map(AbsenceOutput::row).collect()
+  }
+}
+
+/// Whether an absence overlaps the days from `since` to `until` (inclusive).
+fn overlaps(absence: &Absence, since: NaiveDate, until: NaiveDate) -> bool {
+  match (
+    parse_date(&absence.date_since),
+    parse_date(&absence.date_until),
+  ) {
+    (Ok(date_since), Ok(date_until)) => {
+      date_since <= until && date_until >= since
+    }
+    _ => false,
+  }
+}
+
+/// Lists the absences overlapping the given days. Declined and cancelled
+/// absences are only included with `all`.
+pub fn list_absences(
+  format: OutputFormat,
+  since: Option<&str>,
+  until: Option<&str>,
+  users_id: Option<u32>,
+  all: bool,
+) -> Result<(), CliError> {
+  let config = Config::read()?;
+  let api_client = config.api_client()?;
+
+  let year = Local::now().year();
+  let since = match since {
+    Some(since) => parse_date(since)?,
+    None => NaiveDate::from_ymd_opt(year, 1, 1).unwrap(),
+  };
+  let until = match until {
+    Some(until) => parse_date(until)?,
+    None => NaiveDate::from_ymd_opt(since.year(), 12, 31).unwrap(),
+  };
+
+  // The API only filters absences by year, so absences spanning the turn of
+  // a year are returned for both years
+  let mut absences = Vec::new();
+  for year in since.year()..=until.year() {
+    absences.extend(api_client.list_absences(year, users_id)?);
+  }
+  absences.retain(|absence| {
+    overlaps(absence, since, until) && (all || absence.status.is_active())
+  });
+  absences.sort_by(|a, b| (&a.date_since, a.id).cmp(&(&b.date_since, b.id)));
+  absences.dedup_by_key(|absence| absence.id);
+
+  AbsencesOutput(absences.into_iter().map(AbsenceOutput::from).collect())
+    .render(format);
+
+  Ok(())
+}
+
+/// Requests an absence from `since` to `until` (inclusive, defaults to
+/// `since`).
+pub fn request_absence(
+  format: OutputFormat,
+  absence_type: AbsenceType,
+  since: &str,
+  until: Option<&str>,
+  half_day: bool,
+  note: Option<&str>,
+) -> Result<(), CliError> {
+  let config = Config::read()?;
+  let api_client = config.api_client()?;
+
+  let since = parse_date(since)?;
+  let until = until.map(parse_date).transpose()?.unwrap_or(since);
+
+  let absence = api_client.create_absence(&AbsenceParams {
+    absence_type,
+    date_since: since.format(API_DATE_FORMAT).to_string(),
+    date_until: until.format(API_DATE_FORMAT).to_string(),
+    half_day,
+    note,
+  })?;
+
+  if format == OutputFormat::Human {
+    println!("Requested the absence.\n");
+  }
+  AbsenceOutput::from(absence).render(format);
+
+  Ok(())
+}
+
+pub fn cancel_absence(format: OutputFormat, id: u32) -> Result<(), CliError> {
+  let config = Config::read()?;
+  let api_client = config.api_client()?;
+
+  api_client.delete_absence(id)?;
+
+  Message::new(
+    format!("Cancelled absence {id}."),
+    json!({ "cancelled": true, "id": id }),
+  )
+  .render(format);
+
+  Ok(())
+}
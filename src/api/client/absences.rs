@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::{ApiClient, ApiError};
+use crate::api::{Absence, AbsenceType};
+
+const ABSENCES_PATH: &str = "/api/v4/absences";
+
+/// Fields of a new absence request.
+#[derive(Debug)]
+pub struct AbsenceParams<'a> {
+  pub absence_type: AbsenceType,
+  /// First day (`YYYY-MM-DD`)
+  pub date_since: String,
+  /// Last day (`YYYY-MM-DD`, inclusive)
+  pub date_until: String,
+  pub half_day: bool,
+  pub note: Option<&'a str>,
+}
+
+impl AbsenceParams<'_> {
+  fn to_params(&self) -> Vec<(&'static str, String)> {
+    let mut params = vec![
+      ("type", (self.absence_type as u8).to_string()),
+      ("date_since", self.date_since.clone()),
+      ("date_until", self.date_until.clone()),
+      ("half_day", u8::from(self.half_day).to_string()),
+    ];
+    if let Some(note) = self.note {
+      params.push(("note", note.to_string()));
+    }
+    params
+  }
+}
+
+impl ApiClient {
+  /// Lists the absences of a year, optionally only those of one user.
+  pub fn list_absences(
+    &self,
+    year: i32,
+    users_id: Option<u32>,
+  ) -> Result<Vec<Absence>, ApiError> {
+    let mut params = vec![("filter[year]", year.to_string())];
+    if let Some(users_id) = users_id {
+      params.push(("filter[users_id]", users_id.to_string()));
+    }
+    self.list_all(ABSENCES_PATH, "data", &params)
+  }
+
+  pub fn create_absence(
+    &self,
+    params: &AbsenceParams,
+  ) -> Result<Absence, ApiError> {
+    let url = self.url_with_params(ABSENCES_PATH, &params.to_params());
+    let request = self.client.post(url);
+    let mut result: HashMap<String, Value> = Self::parse(self.send(request)?)?;
+    Self::take_key(&mut result, "data")
+  }
+
+  pub fn delete_absence(&self, id: u32) -> Result<(), ApiError> {
+    self.send(
+      self
+        .client
+        .delete(self.url(&format!("{ABSENCES_PATH}/{id}"))),
+    )?;
+    Ok(())
+  }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::{ApiClient, ApiError};
+use crate::api::TimeEntry;
+
+const ENTRIES_PATH: &str = "/api/v2/entries";
+
+/// Fields of a time entry to set when creating or editing it. Fields set to
+/// `None` are omitted from the request, a `projects_id` of `Some(None)`
+/// removes the project.
+#[derive(Debug, Default)]
+pub struct EntryParams<'a> {
+  pub customers_id: Option<u32>,
+  pub projects_id: Option<Option<u32>>,
+  pub services_id: Option<u32>,
+  pub billable: Option<bool>,
+  pub text: Option<&'a str>,
+  pub time_since: Option<String>,
+  pub time_until: Option<String>,
+}
+
+impl EntryParams<'_> {
+  pub(super) fn to_params(&self) -> Vec<(&'static str, String)> {
+    let mut params = Vec::new();
+    if let Some(customers_id) = self.customers_id {
+      params.push(("customers_id", customers_id.to_string()));
+    }
+    if let Some(projects_id) = self.projects_id {
+      let projects_id =
+        projects_id.map(|id| id.to_string()).unwrap_or_default();
+      params.push(("projects_id", projects_id));
+    }
+    if let Some(services_id) = self.services_id {
+      params.push(("services_id", services_id.to_string()));
+    }
+    if let Some(billable) = self.billable {
+      params.push(("billable", u8::from(billable).to_string()));
+    }
+    if let Some(text) = self.text {
+      params.push(("text", text.to_string()));
+    }
+    if let Some(time_since) = &self.time_since {
+      params.push(("time_since", time_since.clone()));
+    }
+    if let Some(time_until) = &self.time_until {
+      params.push(("time_until", time_until.clone()));
+    }
+    params
+  }
+}
+
+impl ApiClient {
+  /// Lists all time entries between `time_since` and `time_until` (API time
+  /// format), optionally only those of one user.
+  pub fn list_entries(
+    &self,
+    time_since: &str,
+    time_until: &str,
+    users_id: Option<u32>,
+  ) -> Result<Vec<TimeEntry>, ApiError> {
+    let mut params = vec![
+      ("time_since", time_since.to_string()),
+      ("time_until", time_until.to_string()),
+    ];
+    if let Some(users_id) = users_id {
+      params.push(("filter[users_id]", users_id.to_string()));
+    }
+    self.list_all(ENTRIES_PATH, "entries", &params)
+  }
+
+  pub fn get_entry(&self, id: u32) -> Result<TimeEntry, ApiError> {
+    let mut result: HashMap<String, Value> =
+      Self::parse(self.get(&format!("{ENTRIES_PATH}/{id}"))?)?;
+    Self::take_key(&mut result, "entry")
+  }
+
+  pub fn create_entry(
+    &self,
+    params: &EntryParams,
+  ) -> Result<TimeEntry, ApiError> {
+    let url = self.url_with_params(ENTRIES_PATH, &params.to_params());
+    let request = self.client.post(url);
+    let mut result: HashMap<String, Value> = Self::parse(self.send(request)?)?;
+    Self::take_key(&mut result, "entry")
+  }
+
+  pub fn update_entry(
+    &self,
+    id: u32,
+    params: &EntryParams,
+  ) -> Result<TimeEntry, ApiError> {
+    let url = self
+      .url_with_params(&format!("{ENTRIES_PATH}/{id}"), &params.to_params());
+    let request = self.client.put(url);
+    let mut result: HashMap<String, Value> = Self::parse(self.send(request)?)?;
+    Self::take_key(&mut result, "entry")
+  }
+
+  pub fn delete_entry(&self, id: u32) -> Result<(), ApiError> {
+    self.send(
+      self
+        .client
+        .delete(self.url(&format!("{ENTRIES_PATH}/{id}"))),
+    )?;
+    Ok(())
+  }
+}
@@ -1,20 +1,30 @@
 use std::collections::HashMap;
 
-use reqwest::{blocking::Client, header::HeaderMap};
-use serde::de::DeserializeOwned;
+use reqwest::{
+  blocking::{Client, RequestBuilder, Response},
+  header::HeaderMap,
+  StatusCode, Url,
+};
+use serde::{de::DeserializeOwned, Deserialize};
 use serde_json::Value;
+use thiserror::Error;
 
+pub mod absences;
+pub mod entries;
 mod stopclock;
+mod user_reports;
+mod users;
 
-const CLIENT_NAME: &'static str = "clockodo-cli";
-const CLIENT_EMAIL: &'static str = "clockodo-cli@zrtx.de";
-const API_URL: &'static str = "https://my.clockodo.com";
+const CLIENT_NAME: &str = "clockodo-cli";
+const CLIENT_EMAIL: &str = "clockodo-cli@zrtx.de";
+pub const DEFAULT_API_URL: &str = "https://my.clockodo.com";
 
 pub trait Resource: DeserializeOwned {
   const PATH_NAME: &'static str;
   const SINGLE_NAME: &'static str;
   const LIST_NAME: &'static str;
 
+  fn id(&self) -> u32;
   fn name(&self) -> &str;
 }
 
@@ -23,71 +33,223 @@ pub trait ResourceFilter {
   type FilterType: ToString;
 }
 
+#[derive(Debug, Error)]
+pub enum ApiError {
+  #[error("{0}")]
+  Request(#[from] reqwest::Error),
+
+  #[error(
+    "{status}{}",
+    message.as_ref().map(|m| format!(": {m}")).unwrap_or_default()
+  )]
+  Status {
+    status: StatusCode,
+    message: Option<String>,
+  },
+
+  #[error("Failed to parse response at `{path}`: {message}")]
+  Deserialize { path: String, message: String },
+
+  #[error("Invalid API URL `{url}`: {message}")]
+  InvalidUrl { url: String, message: String },
+
+  #[error("The {0} contains characters that aren't allowed in HTTP headers")]
+  InvalidHeader(&'static str),
+}
+
+impl ApiError {
+  /// The HTTP status code returned by the API, if the request got that far.
+  pub fn status(&self) -> Option<StatusCode> {
+    match self {
+      ApiError::Request(err) => err.status(),
+      ApiError::Status { status, .. } => Some(*status),
+      ApiError::Deserialize { .. }
+      | ApiError::InvalidUrl { .. }
+      | ApiError::InvalidHeader(_) => None,
+    }
+  }
+}
+
+#[derive(Debug, Deserialize)]
+struct Paging {
+  current_page: u32,
+  count_pages: u32,
+}
+
 pub struct ApiClient {
   client: Client,
+  api_url: Url,
 }
 
 impl<'a> ApiClient {
-  pub fn new(api_user: &'a str, api_key: &'a str) -> Self {
+  pub fn new(
+    api_url: &'a str,
+    api_user: &'a str,
+    api_key: &'a str,
+  ) -> Result<Self, ApiError> {
+    let invalid_url = |message: &str| ApiError::InvalidUrl {
+      url: api_url.to_string(),
+      message: message.to_string(),
+    };
+    let api_url = Url::parse(api_url.trim_end_matches('/'))
+      .map_err(|err| invalid_url(&err.to_string()))?;
+    if !matches!(api_url.scheme(), "http" | "https") {
+      return Err(invalid_url("expected an http or https URL"));
+    }
+
     let mut headers = HeaderMap::new();
     headers.insert(
       "X-Clockodo-External-Application",
       format!("{};{}", CLIENT_NAME, CLIENT_EMAIL).parse().unwrap(),
     );
-    headers.insert("X-ClockodoApiUser", api_user.parse().unwrap());
-    headers.insert("X-ClockodoApiKey", api_key.parse().unwrap());
+    headers.insert(
+      "X-ClockodoApiUser",
+      api_user
+        .parse()
+        .map_err(|_| ApiError::InvalidHeader("API user"))?,
+    );
+    headers.insert(
+      "X-ClockodoApiKey",
+      api_key
+        .parse()
+        .map_err(|_| ApiError::InvalidHeader("API key"))?,
+    );
 
     let client = Client::builder()
       .user_agent(format!("{} ({})", CLIENT_NAME, CLIENT_EMAIL))
       .default_headers(headers)
-      .build()
-      .unwrap();
+      .build()?;
 
-    ApiClient { client }
+    Ok(ApiClient { client, api_url })
   }
 
-  pub fn get(
-    &self,
-    url: &str,
-  ) -> Result<reqwest::blocking::Response, reqwest::Error> {
-    self.client.get(format!("{API_URL}{url}")).send()
+  fn url(&self, path: &str) -> Url {
+    self.url_with_params(path, &[])
   }
 
-  pub fn get_resource<R: Resource>(
-    &self,
-    id: u32,
-  ) -> Result<R, reqwest::Error> {
-    let result = self
-      .get(&format!("{}/{}", R::PATH_NAME, id))?
-      .json::<HashMap<String, Value>>()?;
-    Ok(serde_json::from_value(result[R::SINGLE_NAME].clone()).unwrap())
+  /// Appends `path` to the API base URL, keeping any path prefix of the base
+  /// URL (e.g. of a proxy), and adds the query parameters.
+  fn url_with_params(&self, path: &str, params: &[(&str, String)]) -> Url {
+    let mut url = self.api_url.clone();
+    url.set_path(&format!(
+      "{}{}",
+      self.api_url.path().trim_end_matches('/'),
+      path
+    ));
+    url.set_query(None);
+    if !params.is_empty() {
+      url.query_pairs_mut().extend_pairs(params);
+    }
+    url
+  }
+
+  /// Sends the request and turns non-success responses into
+  /// [`ApiError::Status`], using Clockodo's error message if present.
+  fn send(&self, request: RequestBuilder) -> Result<Response, ApiError> {
+    let response = request.send()?;
+    let status = response.status();
+    if status.is_success() {
+      return Ok(response);
+    }
+
+    let message =
+      response
+        .json::<Value>()
+        .ok()
+        .and_then(|body| match &body["error"] {
+          Value::String(message) => Some(message.clone()),
+          error => error["message"].as_str().map(String::from),
+        });
+
+    Err(ApiError::Status { status, message })
   }
 
-  pub fn list_resources<R: Resource>(&self) -> Result<Vec<R>, reqwest::Error> {
-    let result = self.get(R::PATH_NAME)?.json::<HashMap<String, Value>>()?;
-    Ok(serde_json::from_value(result[R::LIST_NAME].clone()).unwrap())
+  /// Deserializes a response body, reporting the path of the offending value
+  /// on failure.
+  fn parse<T: DeserializeOwned>(response: Response) -> Result<T, ApiError> {
+    let body = response.text()?;
+    let deserializer = &mut serde_json::Deserializer::from_str(&body);
+    serde_path_to_error::deserialize(deserializer).map_err(|err| {
+      ApiError::Deserialize {
+        path: err.path().to_string(),
+        message: err.inner().to_string(),
+      }
+    })
   }
 
-  pub fn list_resources_filtered<R: Resource, F: ResourceFilter>(
+  /// Deserializes the value stored under `key` in a response object.
+  fn take_key<T: DeserializeOwned>(
+    result: &mut HashMap<String, Value>,
+    key: &str,
+  ) -> Result<T, ApiError> {
+    let value = result.remove(key).ok_or_else(|| ApiError::Deserialize {
+      path: key.to_string(),
+      message: "missing key in response".to_string(),
+    })?;
+    serde_path_to_error::deserialize(value).map_err(|err| {
+      ApiError::Deserialize {
+        path: format!("{key}.{}", err.path()),
+        message: err.inner().to_string(),
+      }
+    })
+  }
+
+  /// Fetches every page of a paginated list endpoint and collects the items
+  /// stored under `key`.
+  fn list_all<T: DeserializeOwned>(
     &self,
-    filter: F::FilterType,
-  ) -> Result<Vec<R>, reqwest::Error> {
-    let result = self
-      .get(&format!(
-        "{}/?{}={}",
-        R::PATH_NAME,
-        F::FILTER_NAME,
-        filter.to_string()
-      ))?
-      .json::<HashMap<String, Value>>()?;
-    Ok(serde_json::from_value(result[R::LIST_NAME].clone()).unwrap())
+    path: &str,
+    key: &str,
+    params: &[(&str, String)],
+  ) -> Result<Vec<T>, ApiError> {
+    let mut items = Vec::new();
+    let mut page = 1;
+
+    loop {
+      let mut query = params.to_vec();
+      query.push(("page", page.to_string()));
+      let url = self.url_with_params(path, &query);
+
+      let mut result: HashMap<String, Value> =
+        Self::parse(self.send(self.client.get(url))?)?;
+      let paging = if result.contains_key("paging") {
+        Some(Self::take_key::<Paging>(&mut result, "paging")?)
+      } else {
+        None
+      };
+      items.extend(Self::take_key::<Vec<T>>(&mut result, key)?);
+
+      match paging {
+        Some(paging) if paging.current_page < paging.count_pages => page += 1,
+        _ => break,
+      }
+    }
+
+    Ok(items)
+  }
+
+  pub fn get(&self, url: &str) -> Result<Response, ApiError> {
+    self.send(self.client.get(self.url(url)))
+  }
+
+  pub fn get_resource<R: Resource>(&self, id: u32) -> Result<R, ApiError> {
+    let mut result: HashMap<String, Value> =
+      Self::parse(self.get(&format!("{}/{}", R::PATH_NAME, id))?)?;
+    Self::take_key(&mut result, R::SINGLE_NAME)
   }
 
-  pub fn find_resource_by_name<R: Resource>(
+  pub fn list_resources<R: Resource>(&self) -> Result<Vec<R>, ApiError> {
+    self.list_all(R::PATH_NAME, R::LIST_NAME, &[])
+  }
+
+  pub fn list_resources_filtered<R: Resource, F: ResourceFilter>(
     &self,
-    name: &str,
-  ) -> Result<Option<R>, reqwest::Error> {
-    let resources = self.list_resources::<R>()?;
-    Ok(resources.into_iter().find(|r| r.name() == name))
+    filter: F::FilterType,
+  ) -> Result<Vec<R>, ApiError> {
+    self.list_all(
+      R::PATH_NAME,
+      R::LIST_NAME,
+      &[(F::FILTER_NAME, filter.to_string())],
+    )
   }
 }
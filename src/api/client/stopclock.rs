@@ -1,7 +1,6 @@
-use reqwest::Url;
 use serde::{Deserialize, Serialize};
 
-use super::ApiClient;
+use super::{entries::EntryParams, ApiClient, ApiError};
 use crate::api::TimeEntry;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -16,8 +15,8 @@ pub struct StopResponse {
 }
 
 impl ApiClient {
-  pub fn get_status(&self) -> Result<StatusResponse, reqwest::Error> {
-    Ok(self.get("/api/v2/clock")?.json::<StatusResponse>()?)
+  pub fn get_status(&self) -> Result<StatusResponse, ApiError> {
+    Self::parse(self.get("/api/v2/clock")?)
   }
 
   pub fn start_clock(
@@ -27,7 +26,8 @@ impl ApiClient {
     billable: Option<bool>,
     projects_id: Option<u32>,
     text: Option<&str>,
-  ) -> Result<StatusResponse, reqwest::Error> {
+    time_since: Option<&str>,
+  ) -> Result<StatusResponse, ApiError> {
     let mut params = vec![
       ("customers_id", customers_id.to_string()),
       ("services_id", services_id.to_string()),
@@ -41,34 +41,42 @@ impl ApiClient {
     if let Some(text) = text {
       params.push(("text", text.to_string()));
     }
+    if let Some(time_since) = time_since {
+      params.push(("time_since", time_since.to_string()));
+    }
 
-    let url = Url::parse_with_params(
-      &format!("{}{}", super::API_URL, "/api/v2/clock"),
-      &params,
-    )
-    .unwrap();
+    let url = self.url_with_params("/api/v2/clock", &params);
 
-    Ok(self.client.post(url).send()?.json::<StatusResponse>()?)
+    Self::parse(self.send(self.client.post(url))?)
   }
 
-  pub fn stop_clock(&self) -> Result<Option<StopResponse>, reqwest::Error> {
+  pub fn stop_clock(&self) -> Result<Option<StopResponse>, ApiError> {
     let status = self.get_status()?;
     let entry = match status.running {
       Some(entry) => entry,
       None => return Ok(None),
     };
 
-    Ok(Some(
-      self
-        .client
-        .delete(format!(
-          "{}{}{}",
-          super::API_URL,
-          "/api/v2/clock/",
-          entry.id
-        ))
-        .send()?
-        .json::<StopResponse>()?,
-    ))
+    Ok(Some(Self::parse(self.send(self.client.delete(
+      self.url(&format!("/api/v2/clock/{}", entry.id)),
+    ))?)?))
+  }
+
+  /// Changes fields of the running time entry. The current `time_since` of
+  /// the entry has to be passed along when changing the start time.
+  pub fn update_clock(
+    &self,
+    entry: &TimeEntry,
+    params: &EntryParams,
+  ) -> Result<StatusResponse, ApiError> {
+    let mut params = params.to_params();
+    if params.iter().any(|(key, _)| *key == "time_since") {
+      params.push(("time_since_before", entry.time_since.clone()));
+    }
+
+    let url =
+      self.url_with_params(&format!("/api/v2/clock/{}", entry.id), &params);
+
+    Self::parse(self.send(self.client.put(url))?)
   }
 }
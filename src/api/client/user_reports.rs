@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{ApiClient, ApiError};
+use crate::time::parse_date;
+
+const USER_REPORTS_PATH: &str = "/api/v2/userReports";
+
+/// Level of detail of a user report: the year with its months, weeks and
+/// days.
+const REPORT_TYPE_DAYS: u8 = 3;
+
+/// Target and worked hours of a user in a year, along with their vacation and
+/// overtime balance. Durations are in seconds.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserReport {
+  pub users_id: u32,
+  pub sum_target: i64,
+  pub sum_hours: i64,
+  pub diff: i64,
+
+  /// Vacation days granted for the year
+  pub holidays_quota: f64,
+
+  /// Vacation days carried over from the previous year
+  pub holidays_carry: f64,
+
+  /// Overtime carried over from the previous year
+  #[serde(default)]
+  pub overtime_carryover: i64,
+
+  /// Overtime reduced by payout or absences
+  #[serde(default)]
+  pub overtime_reduced: i64,
+
+  pub sum_absence: AbsenceSums,
+
+  #[serde(default)]
+  pub months: Vec<MonthReport>,
+}
+
+impl UserReport {
+  /// Returns the worked and target hours (in seconds) of the days from
+  /// `since` to `until` (inclusive). Days outside the report's year aren't
+  /// included.
+  pub fn sum_days(
+    &self,
+    since: NaiveDate,
+    until: NaiveDate,
+  ) -> Result<(i64, i64), chrono::ParseError> {
+    let (mut worked, mut target) = (0, 0);
+    let mut dates = Vec::new();
+    for day in self
+      .months
+      .iter()
+      .flat_map(|month| &month.weeks)
+      .flat_map(|week| &week.days)
+    {
+      // Weeks spanning two months may be listed in both
+      let date = parse_date(&day.date)?;
+      if date >= since && date <= until && !dates.contains(&date) {
+        dates.push(date);
+        worked += day.sum_hours;
+        target += day.sum_target;
+      }
+    }
+    Ok((worked, target))
+  }
+}
+
+/// Days of absence in a year, by type.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AbsenceSums {
+  /// Vacation days taken or planned
+  #[serde(default)]
+  pub regular_holidays: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MonthReport {
+  /// Month of the year, starting at 1
+  pub nr: u32,
+  pub sum_target: i64,
+  pub sum_hours: i64,
+  pub diff: i64,
+
+  #[serde(default)]
+  pub weeks: Vec<WeekReport>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WeekReport {
+  /// ISO week number
+  pub nr: u32,
+
+  #[serde(default)]
+  pub days: Vec<DayReport>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DayReport {
+  /// The day (`YYYY-MM-DD`)
+  pub date: String,
+  pub sum_target: i64,
+  pub sum_hours: i64,
+  pub diff: i64,
+}
+
+impl ApiClient {
+  /// Returns the report of a user for a year, down to single days.
+  pub fn get_user_report(
+    &self,
+    users_id: u32,
+    year: i32,
+  ) -> Result<UserReport, ApiError> {
+    let url = self.url_with_params(
+      &format!("{USER_REPORTS_PATH}/{users_id}"),
+      &[
+        ("year", year.to_string()),
+        ("type", REPORT_TYPE_DAYS.to_string()),
+      ],
+    );
+    let mut result: HashMap<String, Value> =
+      Self::parse(self.send(self.client.get(url))?)?;
+    Self::take_key(&mut result, "userreport")
+  }
+}
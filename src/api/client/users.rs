@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+use super::{ApiClient, ApiError};
+use crate::api::{Company, User};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserAggregate {
+  pub user: User,
+  pub company: Company,
+}
+
+impl ApiClient {
+  /// Returns the user the API key belongs to, along with their company.
+  pub fn get_current_user(&self) -> Result<UserAggregate, ApiError> {
+    Self::parse(self.get("/api/v2/aggregates/users/me")?)
+  }
+}
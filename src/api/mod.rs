@@ -1,5 +1,7 @@
 use core::fmt;
 
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
@@ -7,7 +9,8 @@ use self::client::{Resource, ResourceFilter};
 
 pub mod client;
 
-#[derive(Debug, Serialize_repr, Deserialize_repr)]
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Clone, Serialize_repr, Deserialize_repr)]
 #[repr(u8)]
 pub enum Billable {
   NotBillable = 0,
@@ -15,6 +18,12 @@ pub enum Billable {
   AlreadyBilled = 2,
 }
 
+impl Billable {
+  pub fn is_billable(&self) -> bool {
+    !matches!(self, Billable::NotBillable)
+  }
+}
+
 impl fmt::Display for Billable {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     match self {
@@ -25,7 +34,7 @@ impl fmt::Display for Billable {
   }
 }
 
-#[derive(Debug, Serialize_repr, Deserialize_repr)]
+#[derive(Debug, Clone, Serialize_repr, Deserialize_repr)]
 #[repr(u8)]
 pub enum EntryType {
   TimeEntry = 1,
@@ -43,7 +52,106 @@ impl fmt::Display for EntryType {
   }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// The kind of an absence. Values can be passed on the command line in
+/// kebab-case, e.g. `sick-day`.
+#[derive(Debug, Clone, Copy, Serialize_repr, Deserialize_repr, ValueEnum)]
+#[repr(u8)]
+pub enum AbsenceType {
+  #[value(alias = "vacation")]
+  RegularHoliday = 1,
+  SpecialLeave = 2,
+  ReductionOfOvertime = 3,
+  SickDay = 4,
+  SickDayOfChild = 5,
+  SchoolFurtherEducation = 6,
+  MaternityProtection = 7,
+  HomeOffice = 8,
+  WorkOutOfOffice = 9,
+  SpecialLeaveUnpaid = 10,
+  SickDayOfCare = 11,
+  Quarantine = 12,
+  MilitaryService = 13,
+}
+
+impl fmt::Display for AbsenceType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      AbsenceType::RegularHoliday => write!(f, "regular holiday"),
+      AbsenceType::SpecialLeave => write!(f, "special leave"),
+      AbsenceType::ReductionOfOvertime => write!(f, "reduction of overtime"),
+      AbsenceType::SickDay => write!(f, "sick day"),
+      AbsenceType::SickDayOfChild => write!(f, "sick day of child"),
+      AbsenceType::SchoolFurtherEducation => {
+        write!(f, "school / further education")
+      }
+      AbsenceType::MaternityProtection => write!(f, "maternity protection"),
+      AbsenceType::HomeOffice => write!(f, "home office"),
+      AbsenceType::WorkOutOfOffice => write!(f, "work out of office"),
+      AbsenceType::SpecialLeaveUnpaid => write!(f, "special leave (unpaid)"),
+      AbsenceType::SickDayOfCare => write!(f, "sick day of care"),
+      AbsenceType::Quarantine => write!(f, "quarantine"),
+      AbsenceType::MilitaryService => write!(f, "military service"),
+    }
+  }
+}
+
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Serialize_repr, Deserialize_repr,
+)]
+#[repr(u8)]
+pub enum AbsenceStatus {
+  Requested = 0,
+  Approved = 1,
+  Declined = 2,
+  ApprovalCancelled = 3,
+  RequestCancelled = 4,
+}
+
+impl AbsenceStatus {
+  /// Whether the absence was requested or approved, and not cancelled or
+  /// declined since.
+  pub fn is_active(&self) -> bool {
+    matches!(self, AbsenceStatus::Requested | AbsenceStatus::Approved)
+  }
+}
+
+impl fmt::Display for AbsenceStatus {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      AbsenceStatus::Requested => write!(f, "requested"),
+      AbsenceStatus::Approved => write!(f, "approved"),
+      AbsenceStatus::Declined => write!(f, "declined"),
+      AbsenceStatus::ApprovalCancelled => write!(f, "approval cancelled"),
+      AbsenceStatus::RequestCancelled => write!(f, "request cancelled"),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Absence {
+  pub id: u32,
+  pub users_id: u32,
+
+  /// First day of the absence (`YYYY-MM-DD`)
+  pub date_since: String,
+
+  /// Last day of the absence (`YYYY-MM-DD`, inclusive)
+  pub date_until: String,
+
+  pub status: AbsenceStatus,
+
+  #[serde(rename = "type")]
+  pub absence_type: AbsenceType,
+
+  #[serde(default)]
+  pub note: Option<String>,
+
+  /// Number of days the absence counts as (e.g. excluding weekends)
+  #[serde(default)]
+  pub count_days: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimeEntry {
   pub id: u32,
   pub customers_id: u32,
@@ -52,6 +160,14 @@ pub struct TimeEntry {
   pub time_insert: String,
   pub billable: Billable,
   pub time_since: String,
+
+  #[serde(default)]
+  pub time_until: Option<String>,
+
+  /// Duration in seconds (only set for finished entries)
+  #[serde(default)]
+  pub duration: Option<i64>,
+
   pub text: Option<String>,
 
   #[serde(rename = "type")]
@@ -60,7 +176,24 @@ pub struct TimeEntry {
   pub services_id: Option<u32>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+impl TimeEntry {
+  /// Returns the duration of the entry in seconds. For running entries, this
+  /// is the time elapsed since the start.
+  pub fn elapsed(&self) -> Result<i64, chrono::ParseError> {
+    if let Some(duration) = self.duration {
+      return Ok(duration);
+    }
+
+    let since = self.time_since.parse::<DateTime<Utc>>()?;
+    let until = match &self.time_until {
+      Some(time_until) => time_until.parse::<DateTime<Utc>>()?,
+      None => Utc::now(),
+    };
+    Ok(until.signed_duration_since(since).num_seconds())
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct Customer {
   pub id: u32,
   pub name: String,
@@ -71,12 +204,16 @@ impl Resource for Customer {
   const SINGLE_NAME: &'static str = "customer";
   const LIST_NAME: &'static str = "customers";
 
+  fn id(&self) -> u32 {
+    self.id
+  }
+
   fn name(&self) -> &str {
     &self.name
   }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Project {
   pub id: u32,
   pub name: String,
@@ -88,6 +225,10 @@ impl Resource for Project {
   const SINGLE_NAME: &'static str = "project";
   const LIST_NAME: &'static str = "projects";
 
+  fn id(&self) -> u32 {
+    self.id
+  }
+
   fn name(&self) -> &str {
     &self.name
   }
@@ -100,7 +241,7 @@ impl ResourceFilter for CustomersFilter {
   type FilterType = u32;
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Service {
   pub id: u32,
   pub name: String,
@@ -113,7 +254,24 @@ impl Resource for Service {
   const SINGLE_NAME: &'static str = "service";
   const LIST_NAME: &'static str = "services";
 
+  fn id(&self) -> u32 {
+    self.id
+  }
+
   fn name(&self) -> &str {
     &self.name
   }
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+  pub id: u32,
+  pub name: String,
+  pub email: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Company {
+  pub id: u32,
+  pub name: String,
+}
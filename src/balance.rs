@@ -0,0 +1,192 @@
+use chrono::{Datelike, Days, Local, NaiveDate};
+use serde::Serialize;
+
+use crate::{
+  api::client::ApiClient,
+  config::Config,
+  output::{OutputFormat, Render},
+  time::{format_duration_diff, format_duration_hm},
+  CliError,
+};
+
+#[derive(Debug, Serialize)]
+struct VacationBalance {
+  quota: f64,
+  carryover: f64,
+  taken: f64,
+  remaining: f64,
+}
+
+/// Worked and target hours of a period.
+#[derive(Debug, Serialize)]
+struct PeriodBalance {
+  period: &'static str,
+  worked_seconds: i64,
+  worked: String,
+  target_seconds: i64,
+  target: String,
+  diff_seconds: i64,
+  diff: String,
+}
+
+impl PeriodBalance {
+  fn new(period: &'static str, worked: i64, target: i64) -> Self {
+    PeriodBalance {
+      period,
+      worked_seconds: worked,
+      worked: format_duration_hm(worked),
+      target_seconds: target,
+      target: format_duration_hm(target),
+      diff_seconds: worked - target,
+      diff: format_duration_diff(worked - target),
+    }
+  }
+}
+
+#[derive(Debug, Serialize)]
+struct BalanceOutput {
+  year: i32,
+  vacation: VacationBalance,
+  overtime_seconds: i64,
+  overtime: String,
+  periods: Vec<PeriodBalance>,
+}
+
+impl Render for BalanceOutput {
+  fn print_human(&self) {
+    println!("Vacation {}", self.year);
+    println!("  Quota:\t{} days", self.vacation.quota);
+    println!("  Carry-over:\t{} days", self.vacation.carryover);
+    println!("  Taken:\t{} days", self.vacation.taken);
+    println!("  Remaining:\t{} days", self.vacation.remaining);
+    println!("\nOvertime:\t{}\n", self.overtime);
+
+    println!(
+      "{:<6}  {:>7}  {:>7}  {:>7}",
+      "Period", "Worked", "Target", "Diff"
+    );
+    for period in &self.periods {
+      println!(
+        "{:<6}  {:>7}  {:>7}  {:>7}",
+        period.period, period.worked, period.target, period.diff
+      );
+    }
+  }
+
+  fn headers(&self) -> Vec<&str> {
+    vec!["key", "value"]
+  }
+
+  fn rows(&self) -> Vec<Vec<String>> {
+    let mut rows = vec![
+      vec!["year".to_string(), self.year.to_string()],
+      vec![
+        "vacation_quota".to_string(),
+        self.vacation.quota.to_string(),
+      ],
+      vec![
+        "vacation_carryover".to_string(),
+        self.vacation.carryover.to_string(),
+      ],
+      vec![
+        "vacation_taken".to_string(),
+        self.vacation.taken.to_string(),
+      ],
+      vec![
+        "vacation_remaining".to_string(),
+        self.vacation.remaining.to_string(),
+      ],
+      vec!["overtime".to_string(), self.overtime.clone()],
+    ];
+    for period in &self.periods {
+      rows.push(vec![
+        format!("{}_worked", period.period),
+        period.worked.clone(),
+      ]);
+      rows.push(vec![
+        format!("{}_target", period.period),
+        period.target.clone(),
+      ]);
+      rows.push(vec![format!("{}_diff", period.period), period.diff.clone()]);
+    }
+    rows
+  }
+}
+
+/// Returns the worked and target hours (in seconds) of a user on the days
+/// from `since` to `until` (inclusive), fetching the report of every year the
+/// days fall into.
+pub fn sum_report_days(
+  api_client: &ApiClient,
+  users_id: u32,
+  since: NaiveDate,
+  until: NaiveDate,
+) -> Result<(i64, i64), CliError> {
+  let (mut worked, mut target) = (0, 0);
+  for year in since.year()..=until.year() {
+    let report = api_client.get_user_report(users_id, year)?;
+    let (year_worked, year_target) = report.sum_days(since, until)?;
+    worked += year_worked;
+    target += year_target;
+  }
+  Ok((worked, target))
+}
+
+/// Shows the remaining vacation days and the overtime of the current user, as
+/// well as their worked and target hours of the current week, month and the
+/// year.
+pub fn balance(
+  format: OutputFormat,
+  year: Option<i32>,
+) -> Result<(), CliError> {
+  let config = Config::read()?;
+  let api_client = config.api_client()?;
+
+  let today = Local::now().date_naive();
+  let year = year.unwrap_or(today.year());
+  let me = api_client.get_current_user()?;
+  let report = api_client.get_user_report(me.user.id, year)?;
+
+  let mut periods = Vec::new();
+  if year == today.year() {
+    let monday =
+      today - Days::new(today.weekday().num_days_from_monday().into());
+    let (worked, target) =
+      sum_report_days(&api_client, me.user.id, monday, monday + Days::new(6))?;
+    periods.push(PeriodBalance::new("week", worked, target));
+
+    if let Some(month) =
+      report.months.iter().find(|month| month.nr == today.month())
+    {
+      periods.push(PeriodBalance::new(
+        "month",
+        month.sum_hours,
+        month.sum_target,
+      ));
+    }
+  }
+  periods.push(PeriodBalance::new(
+    "year",
+    report.sum_hours,
+    report.sum_target,
+  ));
+
+  let taken = report.sum_absence.regular_holidays;
+  let overtime =
+    report.overtime_carryover + report.diff - report.overtime_reduced;
+  BalanceOutput {
+    year,
+    vacation: VacationBalance {
+      quota: report.holidays_quota,
+      carryover: report.holidays_carry,
+      taken,
+      remaining: report.holidays_quota + report.holidays_carry - taken,
+    },
+    overtime_seconds: overtime,
+    overtime: format_duration_diff(overtime),
+    periods,
+  }
+  .render(format);
+
+  Ok(())
+}
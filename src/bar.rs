@@ -0,0 +1,69 @@
+use clap::ValueEnum;
+use serde_json::json;
+
+use crate::status::TimeEntryOutput;
+
+/// Text shown in status bars while no time entry is running.
+const STOPPED_TEXT: &str = "stopped";
+
+/// Status bars supported by `status --bar`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BarFormat {
+  /// JSON for a Waybar custom module (`"return-type": "json"`)
+  Waybar,
+  /// JSON for an i3blocks block (`format=json`)
+  I3blocks,
+  /// Plain text for a polybar script module
+  Polybar,
+}
+
+impl BarFormat {
+  /// Formats a single status bar update. `text` is the rendered status
+  /// template of the running entry.
+  pub fn line(
+    &self,
+    text: &str,
+    time_entry: Option<&TimeEntryOutput>,
+  ) -> String {
+    let class = match time_entry {
+      Some(_) => "running",
+      None => "stopped",
+    };
+    let text = match time_entry {
+      Some(_) => text,
+      None => STOPPED_TEXT,
+    };
+
+    match self {
+      BarFormat::Waybar => {
+        let tooltip = match time_entry {
+          Some(time_entry) => time_entry.to_string(),
+          None => "No time entry running.".to_string(),
+        };
+        json!({
+          "text": escape_markup(text),
+          "alt": class,
+          "tooltip": escape_markup(tooltip.trim_end()),
+          "class": class,
+        })
+        .to_string()
+      }
+      BarFormat::I3blocks => json!({
+        "full_text": text,
+        "short_text": time_entry
+          .and_then(|time_entry| time_entry.field("duration"))
+          .unwrap_or_else(|| text.to_string()),
+      })
+      .to_string(),
+      BarFormat::Polybar => text.to_string(),
+    }
+  }
+}
+
+/// Escapes text for Waybar, which interprets it as Pango markup.
+fn escape_markup(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+}
@@ -0,0 +1,358 @@
+use std::path::PathBuf;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+
+use crate::{
+  api::{
+    client::{ApiClient, Resource},
+    Customer, CustomersFilter, Project, Service, TimeEntry,
+  },
+  config::Config,
+  matching::{match_by_name, Match},
+  output::{Message, OutputFormat, Render},
+  CliError,
+};
+
+#[derive(Debug, Error)]
+pub enum CacheError {
+  #[error("IO error: {0}")]
+  Io(#[from] std::io::Error),
+
+  #[error("Failed to serialize cache: {0}")]
+  Serialize(#[from] serde_json::Error),
+}
+
+/// The cached resource lists, as stored on disk.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CachedResources {
+  /// Unix timestamp of the last refresh
+  fetched_at: i64,
+  customers: Vec<Customer>,
+  projects: Vec<Project>,
+  services: Vec<Service>,
+}
+
+/// Resources that are kept in the local cache.
+pub trait CachedResource: Resource + Clone {
+  fn cached(resources: &CachedResources) -> &[Self];
+}
+
+impl CachedResource for Customer {
+  fn cached(resources: &CachedResources) -> &[Self] {
+    &resources.customers
+  }
+}
+
+impl CachedResource for Project {
+  fn cached(resources: &CachedResources) -> &[Self] {
+    &resources.projects
+  }
+}
+
+impl CachedResource for Service {
+  fn cached(resources: &CachedResources) -> &[Self] {
+    &resources.services
+  }
+}
+
+/// Turns an account name into a file name, replacing characters that aren't
+/// safe in paths.
+pub fn account_file_name(account: &str) -> String {
+  account
+    .chars()
+    .map(|c| match c {
+      'a'..='z' | 'A'..='Z' | '0'..='9' | '@' | '.' | '-' | '_' => c,
+      _ => '_',
+    })
+    .collect()
+}
+
+/// Names of the customer, project and service a time entry refers to.
+pub struct EntryNames {
+  pub customer: String,
+  pub project: Option<String>,
+  pub service: Option<String>,
+}
+
+/// On-disk cache of customers, projects and services of an account. The
+/// cache is refreshed when it is older than the configured TTL, and at most
+/// once per run when a lookup misses.
+pub struct ResourceCache<'a> {
+  api_client: &'a ApiClient,
+  path: Option<PathBuf>,
+  ttl: i64,
+  account: String,
+  resources: Option<CachedResources>,
+  refreshed: bool,
+}
+
+impl<'a> ResourceCache<'a> {
+  pub fn new(api_client: &'a ApiClient, config: &Config) -> Self {
+    ResourceCache {
+      api_client,
+      path: Self::cache_path(&config.account_name()),
+      ttl: config.cache_ttl() as i64,
+      account: config.account_name(),
+      resources: Self::read(&config.account_name()),
+      refreshed: false,
+    }
+  }
+
+  fn cache_path(account: &str) -> Option<PathBuf> {
+    Some(
+      dirs::cache_dir()?
+        .join("clockodo-cli")
+        .join(format!("{}.json", account_file_name(account))),
+    )
+  }
+
+  /// The account whose resources are cached.
+  pub fn account(&self) -> &str {
+    &self.account
+  }
+
+  /// Reads the cached resources of the given account as they are, without
+  /// checking their age.
+  pub fn read(account: &str) -> Option<CachedResources> {
+    let cache = std::fs::read_to_string(Self::cache_path(account)?).ok()?;
+    serde_json::from_str(&cache).ok()
+  }
+
+  /// Removes the cache file of the given account.
+  pub fn clear(account: &str) -> Result<(), CacheError> {
+    if let Some(path) = Self::cache_path(account) {
+      match std::fs::remove_file(path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+          return Err(err.into())
+        }
+        _ => {}
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Fetches all resources from the API and writes them to the cache.
+  pub fn refresh(&mut self) -> Result<(), CliError> {
+    let resources = CachedResources {
+      fetched_at: Utc::now().timestamp(),
+      customers: self.api_client.list_resources()?,
+      projects: self.api_client.list_resources()?,
+      services: self.api_client.list_resources()?,
+    };
+
+    self.resources = Some(resources);
+    self.refreshed = true;
+    Ok(self.write()?)
+  }
+
+  /// Fetches the projects of a single customer and replaces the cached ones.
+  fn refresh_customer_projects(
+    &mut self,
+    customers_id: u32,
+  ) -> Result<(), CliError> {
+    let projects = self
+      .api_client
+      .list_resources_filtered::<Project, CustomersFilter>(customers_id)?;
+
+    self.resources()?;
+    let resources = self.resources.as_mut().unwrap();
+    resources
+      .projects
+      .retain(|project| project.customers_id != customers_id);
+    resources.projects.extend(projects);
+
+    Ok(self.write()?)
+  }
+
+  fn write(&self) -> Result<(), CacheError> {
+    if let (Some(path), Some(resources)) = (&self.path, &self.resources) {
+      std::fs::create_dir_all(path.parent().unwrap())?;
+      std::fs::write(path, serde_json::to_string(resources)?)?;
+    }
+
+    Ok(())
+  }
+
+  fn resources(&mut self) -> Result<&CachedResources, CliError> {
+    let expired = match &self.resources {
+      Some(resources) => {
+        Utc::now().timestamp() - resources.fetched_at > self.ttl
+      }
+      None => true,
+    };
+    if expired && !self.refreshed {
+      self.refresh()?;
+    }
+
+    Ok(self.resources.as_ref().unwrap())
+  }
+
+  /// Looks up a cached resource, refreshing the cache once if it is missing.
+  fn find<R: CachedResource>(
+    &mut self,
+    predicate: impl Fn(&R) -> bool,
+  ) -> Result<Option<R>, CliError> {
+    if let Some(resource) =
+      R::cached(self.resources()?).iter().find(|r| predicate(r))
+    {
+      return Ok(Some(resource.clone()));
+    }
+    if self.refreshed {
+      return Ok(None);
+    }
+
+    self.refresh()?;
+    Ok(
+      R::cached(self.resources()?)
+        .iter()
+        .find(|r| predicate(r))
+        .cloned(),
+    )
+  }
+
+  /// Returns all cached resources of a type.
+  pub fn list<R: CachedResource>(&mut self) -> Result<&[R], CliError> {
+    Ok(R::cached(self.resources()?))
+  }
+
+  /// Returns the cached resource with the given ID.
+  pub fn find_by_id<R: CachedResource>(
+    &mut self,
+    id: u32,
+  ) -> Result<Option<R>, CliError> {
+    self.find::<R>(|r| r.id() == id)
+  }
+
+  /// Returns the resource with the given ID, falling back to the API if it
+  /// isn't cached.
+  pub fn get<R: CachedResource>(&mut self, id: u32) -> Result<R, CliError> {
+    match self.find_by_id::<R>(id)? {
+      Some(resource) => Ok(resource),
+      None => Ok(self.api_client.get_resource::<R>(id)?),
+    }
+  }
+
+  /// Looks up the names of the customer, project and service a time entry
+  /// refers to.
+  pub fn entry_names(
+    &mut self,
+    entry: &TimeEntry,
+  ) -> Result<EntryNames, CliError> {
+    Ok(EntryNames {
+      customer: self.get::<Customer>(entry.customers_id)?.name,
+      project: match entry.projects_id {
+        Some(projects_id) => Some(self.get::<Project>(projects_id)?.name),
+        None => None,
+      },
+      service: match entry.services_id {
+        Some(services_id) => Some(self.get::<Service>(services_id)?.name),
+        None => None,
+      },
+    })
+  }
+
+  /// Matches a user-supplied name against the cached resources (see
+  /// [`match_by_name`]), refreshing the cache once if nothing matches.
+  pub fn match_name<R: CachedResource>(
+    &mut self,
+    query: &str,
+  ) -> Result<Match<R>, CliError> {
+    let found =
+      match_by_name(R::cached(self.resources()?), query).map(R::clone);
+    if !matches!(found, Match::NotFound) || self.refreshed {
+      return Ok(found);
+    }
+
+    self.refresh()?;
+    Ok(match_by_name(R::cached(self.resources()?), query).map(R::clone))
+  }
+
+  /// Returns the projects of a customer. If none are cached, only that
+  /// customer's projects are refreshed.
+  pub fn customer_projects(
+    &mut self,
+    customers_id: u32,
+  ) -> Result<Vec<Project>, CliError> {
+    let projects = |resources: &CachedResources| -> Vec<Project> {
+      resources
+        .projects
+        .iter()
+        .filter(|project| project.customers_id == customers_id)
+        .cloned()
+        .collect()
+    };
+
+    let found = projects(self.resources()?);
+    if !found.is_empty() || self.refreshed {
+      return Ok(found);
+    }
+
+    self.refresh_customer_projects(customers_id)?;
+    Ok(projects(self.resources()?))
+  }
+
+  /// Matches a user-supplied name against the projects of a customer. If
+  /// nothing matches, only that customer's projects are refreshed.
+  pub fn match_customer_project(
+    &mut self,
+    customers_id: u32,
+    query: &str,
+  ) -> Result<Match<Project>, CliError> {
+    let projects = |resources: &CachedResources| -> Vec<Project> {
+      resources
+        .projects
+        .iter()
+        .filter(|project| project.customers_id == customers_id)
+        .cloned()
+        .collect()
+    };
+
+    let found =
+      match_by_name(&projects(self.resources()?), query).map(Project::clone);
+    if !matches!(found, Match::NotFound) || self.refreshed {
+      return Ok(found);
+    }
+
+    self.refresh_customer_projects(customers_id)?;
+    Ok(match_by_name(&projects(self.resources()?), query).map(Project::clone))
+  }
+}
+
+pub fn refresh_cache(format: OutputFormat) -> Result<(), CliError> {
+  let config = Config::read()?;
+  let api_client = config.api_client()?;
+  let mut resources = ResourceCache::new(&api_client, &config);
+
+  resources.refresh()?;
+  let cached = resources.resources.as_ref().unwrap();
+
+  Message::new(
+    format!(
+      "Cached {} customers, {} projects and {} services.",
+      cached.customers.len(),
+      cached.projects.len(),
+      cached.services.len()
+    ),
+    json!({
+      "customers": cached.customers.len(),
+      "projects": cached.projects.len(),
+      "services": cached.services.len(),
+    }),
+  )
+  .render(format);
+
+  Ok(())
+}
+
+pub fn clear_cache(format: OutputFormat) -> Result<(), CliError> {
+  let config = Config::read()?;
+  ResourceCache::clear(&config.account_name())?;
+
+  Message::new("Cleared the cache.", json!({ "cleared": true })).render(format);
+
+  Ok(())
+}
@@ -0,0 +1,91 @@
+use clap::ValueEnum;
+use clap_complete::{
+  env::{Bash, Elvish, EnvCompleter, Fish, Powershell, Zsh},
+  CompletionCandidate,
+};
+
+use crate::{
+  api::{Customer, Project, Service},
+  cache::{CachedResource, CachedResources, ResourceCache},
+  config::Config,
+  CliError,
+};
+
+/// Environment variable that switches the binary into completion mode.
+pub const COMPLETE_ENV: &str = "COMPLETE";
+
+const BIN_NAME: &str = "clockodo-cli";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompletionShell {
+  Bash,
+  Zsh,
+  Fish,
+  Elvish,
+  Powershell,
+}
+
+/// Prints the completion script of the given shell. The script calls back
+/// into the binary, so customer, project and service names are completed
+/// from the current cache.
+pub fn completions(shell: CompletionShell) -> Result<(), CliError> {
+  let completer: &dyn EnvCompleter = match shell {
+    CompletionShell::Bash => &Bash,
+    CompletionShell::Zsh => &Zsh,
+    CompletionShell::Fish => &Fish,
+    CompletionShell::Elvish => &Elvish,
+    CompletionShell::Powershell => &Powershell,
+  };
+  let current_exe = std::env::current_exe()?;
+
+  completer.write_registration(
+    COMPLETE_ENV,
+    BIN_NAME,
+    BIN_NAME,
+    &current_exe.to_string_lossy(),
+    &mut std::io::stdout(),
+  )?;
+
+  Ok(())
+}
+
+/// Reads the cached resources without touching the network, completions
+/// have to be fast.
+fn cached() -> Option<CachedResources> {
+  ResourceCache::read(&Config::read().ok()?.account_name())
+}
+
+fn candidates<R: CachedResource>(
+  help: impl Fn(&CachedResources, &R) -> Option<String>,
+) -> Vec<CompletionCandidate> {
+  let resources = match cached() {
+    Some(resources) => resources,
+    None => return vec![],
+  };
+
+  R::cached(&resources)
+    .iter()
+    .map(|resource| {
+      CompletionCandidate::new(resource.name())
+        .help(help(&resources, resource).map(Into::into))
+    })
+    .collect()
+}
+
+pub fn complete_customers() -> Vec<CompletionCandidate> {
+  candidates::<Customer>(|_, _| None)
+}
+
+/// Completes project names, showing the customer of each project.
+pub fn complete_projects() -> Vec<CompletionCandidate> {
+  candidates::<Project>(|resources, project| {
+    Customer::cached(resources)
+      .iter()
+      .find(|customer| customer.id == project.customers_id)
+      .map(|customer| customer.name.clone())
+  })
+}
+
+pub fn complete_services() -> Vec<CompletionCandidate> {
+  candidates::<Service>(|_, _| None)
+}
@@ -1,10 +1,132 @@
+use std::{
+  collections::BTreeMap,
+  io::ErrorKind,
+  path::{Path, PathBuf},
+  sync::OnceLock,
+};
+
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::{
+  api::client::{ApiClient, ApiError, DEFAULT_API_URL},
+  credentials::{
+    keyring_get, keyring_set, run_api_key_cmd, CredentialBackend,
+    CredentialError,
+  },
+};
+
+/// Environment variable overriding the configured API base URL.
+const API_URL_ENV: &str = "CLOCKODO_API_URL";
+
+/// Environment variable selecting the profile.
+pub const PROFILE_ENV: &str = "CLOCKODO_PROFILE";
+
+/// Environment variable overriding the path of the config file.
+const CONFIG_ENV: &str = "CLOCKODO_CONFIG";
+
+/// Environment variables overriding the API credentials.
+const API_USER_ENV: &str = "CLOCKODO_API_USER";
+const API_KEY_ENV: &str = "CLOCKODO_API_KEY";
+
+/// Name referring to the top-level account settings.
+const DEFAULT_PROFILE: &str = "default";
+
+/// Profile selected with `--profile`.
+static SELECTED_PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Config file selected with `--config`.
+static SELECTED_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Default lifetime of the resource cache in seconds.
+const DEFAULT_CACHE_TTL: u64 = 24 * 60 * 60;
+
+/// Template used by `status --template` if none is given or configured.
+const DEFAULT_STATUS_TEMPLATE: &str = "{customer} · {service} · {duration}";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Config {
   pub api_user: String,
+
+  /// The API key, if stored with the file credential backend
+  #[serde(default, skip_serializing_if = "String::is_empty")]
   pub api_key: String,
+
+  /// Where the API key is stored (defaults to `command` if `api_key_cmd` is
+  /// set, `file` otherwise)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub credential_backend: Option<CredentialBackend>,
+
+  /// Command printing the API key, e.g. `pass show clockodo`
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub api_key_cmd: Option<String>,
+
+  /// Base URL of the Clockodo API (defaults to `https://my.clockodo.com`)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub api_url: Option<String>,
+
+  /// Lifetime of the local customer/project/service cache in seconds
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub cache_ttl: Option<u64>,
+
+  /// Default template of `status --template`
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub status_template: Option<String>,
+
+  /// Named `start` presets
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub presets: BTreeMap<String, Preset>,
+
+  /// Named profiles with their own account settings
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub profiles: BTreeMap<String, Profile>,
+
+  /// The active profile, whose account settings have been swapped into the
+  /// top-level fields (`None` for the default account)
+  #[serde(skip)]
+  pub profile: Option<String>,
+
+  /// The API user stored in the file, if overridden by `CLOCKODO_API_USER`
+  #[serde(skip)]
+  stored_api_user: Option<String>,
+}
+
+/// The account settings of a named profile, overriding the top-level ones.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profile {
+  #[serde(default)]
+  pub api_user: String,
+
+  #[serde(default, skip_serializing_if = "String::is_empty")]
+  pub api_key: String,
+
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub credential_backend: Option<CredentialBackend>,
+
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub api_key_cmd: Option<String>,
+
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub api_url: Option<String>,
+}
+
+/// A named combination of `start` arguments.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Preset {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub customer: Option<String>,
+
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub project: Option<String>,
+
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub service: Option<String>,
+
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub billable: Option<bool>,
+
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub description: Option<String>,
 }
 
 #[derive(Debug, Error)]
@@ -17,11 +139,45 @@ pub enum ConfigError {
 
   #[error("Failed to serialize config: {0}")]
   TomlSerialize(#[from] toml::ser::Error),
+
+  #[error(transparent)]
+  Credentials(#[from] CredentialError),
+
+  #[error(transparent)]
+  Api(#[from] ApiError),
+
+  #[error(
+    "Not logged in, run `clockodo-cli login` or set CLOCKODO_API_USER and \
+     CLOCKODO_API_KEY"
+  )]
+  NotLoggedIn,
+
+  #[error(
+    "Profile {0} doesn't exist, log in with `--profile {0}` to create it"
+  )]
+  ProfileNotFound(String),
+
+  #[error(
+    "The API user is set by CLOCKODO_API_USER, unset it to log out of the \
+     stored account"
+  )]
+  LogoutWithEnvUser,
 }
 
 impl Config {
-  #[inline]
-  fn config_path() -> Result<std::path::PathBuf, ConfigError> {
+  /// Selects the config file, taking precedence over `CLOCKODO_CONFIG`.
+  pub fn select_path(path: PathBuf) {
+    SELECTED_PATH.set(path).unwrap();
+  }
+
+  fn config_path() -> Result<PathBuf, ConfigError> {
+    if let Some(path) = SELECTED_PATH.get() {
+      return Ok(path.clone());
+    }
+    if let Some(path) = env_var(CONFIG_ENV) {
+      return Ok(path.into());
+    }
+
     let config_path = dirs::config_dir()
       .ok_or_else(|| {
         std::io::Error::new(std::io::ErrorKind::NotFound, "No config dir found")
@@ -32,22 +188,288 @@ impl Config {
     Ok(config_path)
   }
 
-  pub fn read() -> Result<Self, ConfigError> {
+  /// Selects the profile used by [`Config::read`]. Without a selection, the
+  /// `CLOCKODO_PROFILE` environment variable is used.
+  pub fn select_profile(profile: String) {
+    SELECTED_PROFILE.set(profile).unwrap();
+  }
+
+  fn selected_profile() -> Option<String> {
+    SELECTED_PROFILE
+      .get()
+      .cloned()
+      .or_else(|| env_var(PROFILE_ENV))
+      .filter(|profile| profile != DEFAULT_PROFILE)
+  }
+
+  fn read_file() -> Result<Self, ConfigError> {
     let config_path = Self::config_path()?;
-    let config = std::fs::read_to_string(config_path)?;
+    let config = std::fs::read_to_string(&config_path)?;
     let config: Config = toml::from_str(&config)?;
 
+    let has_api_key = !config.api_key.is_empty()
+      || config.profiles.values().any(|p| !p.api_key.is_empty());
+    if has_api_key {
+      warn_if_readable(&config_path);
+    }
+
     Ok(config)
   }
 
+  /// Reads the config with the selected profile activated, and the API user
+  /// overridden by `CLOCKODO_API_USER`. If both `CLOCKODO_API_USER` and
+  /// `CLOCKODO_API_KEY` are set, the config file is optional.
+  pub fn read() -> Result<Self, ConfigError> {
+    let mut config = match Self::read_file() {
+      Err(ConfigError::Io(err)) if err.kind() == ErrorKind::NotFound => {
+        if env_var(API_USER_ENV).is_none() || env_var(API_KEY_ENV).is_none() {
+          return Err(ConfigError::NotLoggedIn);
+        }
+        Config::default()
+      }
+      config => config?,
+    };
+    if let Some(profile) = Self::selected_profile() {
+      if !config.profiles.contains_key(&profile) {
+        return Err(ConfigError::ProfileNotFound(profile));
+      }
+      config.activate_profile(profile);
+    }
+    if let Some(api_user) = env_var(API_USER_ENV) {
+      config.stored_api_user =
+        Some(std::mem::replace(&mut config.api_user, api_user));
+    }
+
+    Ok(config)
+  }
+
+  /// Like [`Config::read`], but starts with an empty config if there is
+  /// none yet and creates the selected profile if it doesn't exist.
+  pub fn read_or_create() -> Result<Self, ConfigError> {
+    let mut config = match Self::read_file() {
+      Err(ConfigError::Io(err)) if err.kind() == ErrorKind::NotFound => {
+        Config::default()
+      }
+      config => config?,
+    };
+    if let Some(profile) = Self::selected_profile() {
+      config.profiles.entry(profile.clone()).or_default();
+      config.activate_profile(profile);
+    }
+
+    Ok(config)
+  }
+
+  /// Swaps the credentials of the given profile with the top-level ones.
+  /// Calling it again with the same profile swaps them back. The API URL
+  /// stays in place, see [`Config::api_url`].
+  fn swap_profile(&mut self, profile: &str) {
+    let stored = self.profiles.get_mut(profile).unwrap();
+    std::mem::swap(&mut self.api_user, &mut stored.api_user);
+    std::mem::swap(&mut self.api_key, &mut stored.api_key);
+    std::mem::swap(
+      &mut self.credential_backend,
+      &mut stored.credential_backend,
+    );
+    std::mem::swap(&mut self.api_key_cmd, &mut stored.api_key_cmd);
+  }
+
+  fn activate_profile(&mut self, profile: String) {
+    self.swap_profile(&profile);
+    self.profile = Some(profile);
+  }
+
+  /// Removes the account settings of the active profile. Named profiles are
+  /// removed entirely. Fails if the API user is overridden by
+  /// `CLOCKODO_API_USER`, as the stored account isn't the active one then.
+  pub fn remove_account(&mut self) -> Result<(), ConfigError> {
+    if self.stored_api_user.is_some() {
+      return Err(ConfigError::LogoutWithEnvUser);
+    }
+
+    match self.profile.take() {
+      Some(profile) => {
+        self.swap_profile(&profile);
+        self.profiles.remove(&profile);
+      }
+      None => {
+        self.api_user.clear();
+        self.api_key.clear();
+        self.credential_backend = None;
+        self.api_key_cmd = None;
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Name of the active profile, for display.
+  pub fn profile_name(&self) -> &str {
+    self.profile.as_deref().unwrap_or(DEFAULT_PROFILE)
+  }
+
+  /// Identifies the account of the active profile, e.g. in the keyring and
+  /// the cache.
+  pub fn account_name(&self) -> String {
+    match &self.profile {
+      Some(profile) => format!("{}:{}", profile, self.api_user),
+      None => self.api_user.clone(),
+    }
+  }
+
+  /// Writes the config file, readable only by the owner.
   pub fn write(&self) -> Result<(), ConfigError> {
     let config_path = Self::config_path()?;
     let config_dir = config_path.parent().unwrap();
     std::fs::create_dir_all(config_dir)?;
 
-    let config = toml::to_string_pretty(self)?;
-    std::fs::write(config_path, config)?;
+    // Undo the environment override and store the active profile's settings
+    // in its own table again
+    let mut config = self.clone();
+    if let Some(api_user) = config.stored_api_user.take() {
+      config.api_user = api_user;
+    }
+    if let Some(profile) = &self.profile {
+      config.swap_profile(profile);
+    }
+    write_private(&config_path, &toml::to_string_pretty(&config)?)?;
+
+    Ok(())
+  }
+
+  pub fn credential_backend(&self) -> CredentialBackend {
+    match (self.credential_backend, &self.api_key_cmd) {
+      (Some(backend), _) => backend,
+      (None, Some(_)) => CredentialBackend::Command,
+      (None, None) => CredentialBackend::File,
+    }
+  }
+
+  /// Returns the API key from `CLOCKODO_API_KEY` or the configured
+  /// credential backend.
+  pub fn api_key(&self) -> Result<String, ConfigError> {
+    if self.api_user.is_empty() {
+      return Err(ConfigError::NotLoggedIn);
+    }
+    if let Some(api_key) = env_var(API_KEY_ENV) {
+      return Ok(api_key);
+    }
+
+    Ok(match self.credential_backend() {
+      CredentialBackend::File => self.api_key.clone(),
+      CredentialBackend::Keyring => {
+        keyring_get(&self.account_name())?.trim().to_string()
+      }
+      CredentialBackend::Command => run_api_key_cmd(
+        self
+          .api_key_cmd
+          .as_deref()
+          .ok_or(CredentialError::MissingCommand)?,
+      )?,
+    })
+  }
+
+  /// Returns a client for the API, authenticated with the stored
+  /// credentials.
+  pub fn api_client(&self) -> Result<ApiClient, ConfigError> {
+    Ok(ApiClient::new(
+      &self.api_url(),
+      &self.api_user,
+      &self.api_key()?,
+    )?)
+  }
+
+  /// Stores the API key in the configured credential backend. With the
+  /// command backend, the key is managed externally and nothing is stored.
+  pub fn set_api_key(&mut self, api_key: String) -> Result<(), ConfigError> {
+    self.api_key = match self.credential_backend() {
+      CredentialBackend::File => api_key,
+      CredentialBackend::Keyring => {
+        keyring_set(&self.account_name(), &api_key)?;
+        String::new()
+      }
+      CredentialBackend::Command => String::new(),
+    };
 
     Ok(())
   }
+
+  /// Returns the API base URL to use. The `CLOCKODO_API_URL` environment
+  /// variable takes precedence over the `api_url` option of the active
+  /// profile, which takes precedence over the top-level one.
+  pub fn api_url(&self) -> String {
+    let profile_url = self
+      .profile
+      .as_ref()
+      .and_then(|profile| self.profiles[profile].api_url.clone());
+
+    env_var(API_URL_ENV)
+      .or(profile_url)
+      .or_else(|| self.api_url.clone())
+      .unwrap_or_else(|| DEFAULT_API_URL.to_string())
+  }
+
+  pub fn cache_ttl(&self) -> u64 {
+    self.cache_ttl.unwrap_or(DEFAULT_CACHE_TTL)
+  }
+
+  pub fn status_template(&self) -> &str {
+    self
+      .status_template
+      .as_deref()
+      .unwrap_or(DEFAULT_STATUS_TEMPLATE)
+  }
 }
+
+/// Returns the trimmed value of an environment variable, treating empty
+/// values as unset.
+fn env_var(name: &str) -> Option<String> {
+  std::env::var(name)
+    .ok()
+    .map(|value| value.trim().to_string())
+    .filter(|value| !value.is_empty())
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+  use std::{
+    fs::{OpenOptions, Permissions},
+    io::Write,
+    os::unix::fs::{OpenOptionsExt, PermissionsExt},
+  };
+
+  let mut file = OpenOptions::new()
+    .write(true)
+    .create(true)
+    .truncate(true)
+    .mode(0o600)
+    .open(path)?;
+  // The mode only applies to new files
+  file.set_permissions(Permissions::from_mode(0o600))?;
+  file.write_all(contents.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+  std::fs::write(path, contents)
+}
+
+/// Warns if the config file containing the API key can be read by other
+/// users.
+#[cfg(unix)]
+fn warn_if_readable(path: &Path) {
+  use std::os::unix::fs::PermissionsExt;
+
+  if let Ok(metadata) = std::fs::metadata(path) {
+    if metadata.permissions().mode() & 0o044 != 0 {
+      eprintln!(
+        "Warning: {} contains your API key and is readable by other users, \
+         run `chmod 600` on it or log in again",
+        path.display()
+      );
+    }
+  }
+}
+
+#[cfg(not(unix))]
+fn warn_if_readable(_path: &Path) {}
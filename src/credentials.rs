@@ -0,0 +1,81 @@
+use std::process::Command;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Service name under which API keys are stored in the keyring.
+const KEYRING_SERVICE: &str = "clockodo-cli";
+
+/// Where the API key is stored.
+#[derive(
+  Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum CredentialBackend {
+  /// In the config file (readable only by the owner)
+  #[default]
+  File,
+  /// In the OS keyring (Secret Service, macOS Keychain, Windows Credential
+  /// Manager)
+  Keyring,
+  /// Printed by the `api_key_cmd` command, e.g. `pass show clockodo`
+  Command,
+}
+
+#[derive(Debug, Error)]
+pub enum CredentialError {
+  #[error("Keyring error: {0}")]
+  Keyring(#[from] keyring::Error),
+
+  #[error("Failed to run api_key_cmd: {0}")]
+  Io(#[from] std::io::Error),
+
+  #[error("api_key_cmd failed: {0}")]
+  CommandFailed(String),
+
+  #[error("The command credential backend requires api_key_cmd to be set")]
+  MissingCommand,
+}
+
+fn keyring_entry(account: &str) -> Result<keyring::Entry, CredentialError> {
+  Ok(keyring::Entry::new(KEYRING_SERVICE, account)?)
+}
+
+pub fn keyring_get(account: &str) -> Result<String, CredentialError> {
+  Ok(keyring_entry(account)?.get_password()?)
+}
+
+pub fn keyring_delete(account: &str) -> Result<(), CredentialError> {
+  match keyring_entry(account)?.delete_credential() {
+    Err(keyring::Error::NoEntry) => Ok(()),
+    result => Ok(result?),
+  }
+}
+
+pub fn keyring_set(
+  account: &str,
+  api_key: &str,
+) -> Result<(), CredentialError> {
+  Ok(keyring_entry(account)?.set_password(api_key)?)
+}
+
+/// Runs `api_key_cmd` through the shell and returns the first line of its
+/// output.
+pub fn run_api_key_cmd(command: &str) -> Result<String, CredentialError> {
+  #[cfg(windows)]
+  let output = Command::new("cmd").args(["/C", command]).output()?;
+  #[cfg(not(windows))]
+  let output = Command::new("sh").args(["-c", command]).output()?;
+
+  if !output.status.success() {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    return Err(CredentialError::CommandFailed(match stderr.trim() {
+      "" => output.status.to_string(),
+      stderr => stderr.to_string(),
+    }));
+  }
+
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  Ok(stdout.lines().next().unwrap_or_default().trim().to_string())
+}
@@ -0,0 +1,76 @@
+use crate::{
+  api::client::entries::EntryParams,
+  cache::ResourceCache,
+  config::Config,
+  output::{OutputFormat, Render},
+  start::{find_customer, find_edited_project, find_service, StartError},
+  state::ClockState,
+  status::{StatusOutput, TimeEntryOutput},
+  stop::StopError,
+  time::{parse_time, to_api_time},
+  CliError,
+};
+
+#[allow(clippy::too_many_arguments)]
+pub fn edit(
+  format: OutputFormat,
+  customer: Option<&str>,
+  service: Option<&str>,
+  project: Option<&str>,
+  no_project: bool,
+  billable: Option<bool>,
+  description: Option<&str>,
+  since: Option<&str>,
+) -> Result<(), CliError> {
+  let config = Config::read()?;
+  let api_client = config.api_client()?;
+  let mut resources = ResourceCache::new(&api_client, &config);
+
+  let entry = api_client
+    .get_status()?
+    .running
+    .ok_or(StopError::NotRunning)?;
+
+  let customer = customer
+    .map(|customer| find_customer(&mut resources, customer))
+    .transpose()?;
+  let service = service
+    .map(|service| find_service(&mut resources, service))
+    .transpose()?;
+  let project = find_edited_project(
+    &mut resources,
+    customer.as_ref(),
+    || Ok(entry.customers_id),
+    project,
+    no_project,
+  )?;
+
+  let response = api_client.update_clock(
+    &entry,
+    &EntryParams {
+      customers_id: customer.map(|c| c.id),
+      projects_id: project.map(|p| p.map(|p| p.id)),
+      services_id: service.map(|s| s.id),
+      billable,
+      text: description,
+      time_since: since.map(parse_time).transpose()?.map(to_api_time),
+      time_until: None,
+    },
+  )?;
+
+  let entry = response.running.ok_or(StartError::FailedToStart)?;
+  ClockState::update(&mut resources, Some(&entry))?;
+  let output = TimeEntryOutput::resolve(&mut resources, entry)?;
+
+  if format == OutputFormat::Human {
+    println!("Updated the running time entry.\n");
+  }
+  StatusOutput {
+    running: true,
+    time_entry: Some(output),
+    progress: Vec::new(),
+  }
+  .render(format);
+
+  Ok(())
+}
@@ -0,0 +1,189 @@
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::{
+  api::client::entries::EntryParams,
+  cache::ResourceCache,
+  config::Config,
+  output::{Message, OutputFormat, Render},
+  report::EntryUsers,
+  start::{find_customer, find_edited_project, find_project, find_service},
+  status::TimeEntryOutput,
+  time::{parse_time, start_of_today, to_api_time},
+  CliError,
+};
+
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+struct EntriesOutput(Vec<TimeEntryOutput>);
+
+impl Render for EntriesOutput {
+  fn print_human(&self) {
+    if self.0.is_empty() {
+      println!("No time entries found.");
+    }
+    for (i, entry) in self.0.iter().enumerate() {
+      if i > 0 {
+        println!();
+      }
+      entry.print_human();
+    }
+  }
+
+  fn headers(&self) -> Vec<&str> {
+    TimeEntryOutput::HEADERS.to_vec()
+  }
+
+  fn rows(&self) -> Vec<Vec<String>> {
+    self.0.iter().map(TimeEntryOutput::row).collect()
+  }
+}
+
+pub fn list_entries(
+  format: OutputFormat,
+  since: Option<&str>,
+  until: Option<&str>,
+  users: EntryUsers,
+) -> Result<(), CliError> {
+  let config = Config::read()?;
+  let api_client = config.api_client()?;
+  let mut resources = ResourceCache::new(&api_client, &config);
+
+  let since = match since {
+    Some(since) => parse_time(since)?,
+    None => start_of_today(),
+  };
+  let until = match until {
+    Some(until) => parse_time(until)?,
+    None => Utc::now(),
+  };
+
+  let entries = api_client.list_entries(
+    &to_api_time(since),
+    &to_api_time(until),
+    users.users_id(&api_client)?,
+  )?;
+  let entries = entries
+    .into_iter()
+    .map(|entry| TimeEntryOutput::resolve(&mut resources, entry))
+    .collect::<Result<Vec<_>, _>>()?;
+
+  EntriesOutput(entries).render(format);
+
+  Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn add_entry(
+  format: OutputFormat,
+  customer: &str,
+  service: &str,
+  project: Option<&str>,
+  billable: Option<bool>,
+  description: Option<&str>,
+  since: &str,
+  until: &str,
+) -> Result<(), CliError> {
+  let config = Config::read()?;
+  let api_client = config.api_client()?;
+  let mut resources = ResourceCache::new(&api_client, &config);
+
+  let customer = find_customer(&mut resources, customer)?;
+  let service = find_service(&mut resources, service)?;
+  let project = project
+    .map(|project| find_project(&mut resources, &customer, project))
+    .transpose()?;
+
+  let entry = api_client.create_entry(&EntryParams {
+    customers_id: Some(customer.id),
+    projects_id: project.as_ref().map(|p| Some(p.id)),
+    services_id: Some(service.id),
+    billable: Some(billable.unwrap_or(false)),
+    text: description,
+    time_since: Some(to_api_time(parse_time(since)?)),
+    time_until: Some(to_api_time(parse_time(until)?)),
+  })?;
+
+  let output = TimeEntryOutput::from_time_entry(
+    entry,
+    &customer.name,
+    Some(&service.name),
+    project.as_ref().map(|p| p.name.as_str()),
+  )?;
+
+  if format == OutputFormat::Human {
+    println!("Created the time entry.\n");
+  }
+  output.render(format);
+
+  Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn edit_entry(
+  format: OutputFormat,
+  id: u32,
+  customer: Option<&str>,
+  service: Option<&str>,
+  project: Option<&str>,
+  no_project: bool,
+  billable: Option<bool>,
+  description: Option<&str>,
+  since: Option<&str>,
+  until: Option<&str>,
+) -> Result<(), CliError> {
+  let config = Config::read()?;
+  let api_client = config.api_client()?;
+  let mut resources = ResourceCache::new(&api_client, &config);
+
+  let customer = customer
+    .map(|customer| find_customer(&mut resources, customer))
+    .transpose()?;
+  let service = service
+    .map(|service| find_service(&mut resources, service))
+    .transpose()?;
+  let project = find_edited_project(
+    &mut resources,
+    customer.as_ref(),
+    || Ok(api_client.get_entry(id)?.customers_id),
+    project,
+    no_project,
+  )?;
+
+  let entry = api_client.update_entry(
+    id,
+    &EntryParams {
+      customers_id: customer.map(|c| c.id),
+      projects_id: project.map(|p| p.map(|p| p.id)),
+      services_id: service.map(|s| s.id),
+      billable,
+      text: description,
+      time_since: since.map(parse_time).transpose()?.map(to_api_time),
+      time_until: until.map(parse_time).transpose()?.map(to_api_time),
+    },
+  )?;
+  let output = TimeEntryOutput::resolve(&mut resources, entry)?;
+
+  if format == OutputFormat::Human {
+    println!("Updated the time entry.\n");
+  }
+  output.render(format);
+
+  Ok(())
+}
+
+pub fn delete_entry(format: OutputFormat, id: u32) -> Result<(), CliError> {
+  let config = Config::read()?;
+  let api_client = config.api_client()?;
+
+  api_client.delete_entry(id)?;
+
+  Message::new(
+    format!("Deleted time entry {id}."),
+    json!({ "deleted": true, "id": id }),
+  )
+  .render(format);
+
+  Ok(())
+}
@@ -0,0 +1,223 @@
+use std::io::{ErrorKind, Write};
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::{
+  api::TimeEntry,
+  cache::ResourceCache,
+  config::Config,
+  output::{write_table, write_tsv, OutputFormat},
+  report::{EntryUsers, ReportRange},
+  time::{format_duration_hm, format_time, to_api_time},
+  CliError,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+  Csv,
+  Markdown,
+  Json,
+  Ndjson,
+  Yaml,
+  /// Aligned columns
+  Table,
+  /// Tab-separated values
+  Tsv,
+}
+
+impl From<OutputFormat> for ExportFormat {
+  /// The export format matching the global `--format`, with CSV in place of
+  /// the human-readable format.
+  fn from(format: OutputFormat) -> Self {
+    match format {
+      OutputFormat::Human => ExportFormat::Csv,
+      OutputFormat::Json => ExportFormat::Json,
+      OutputFormat::Yaml => ExportFormat::Yaml,
+      OutputFormat::Table => ExportFormat::Table,
+      OutputFormat::Tsv => ExportFormat::Tsv,
+    }
+  }
+}
+
+const HEADERS: [&str; 10] = [
+  "id",
+  "customer",
+  "project",
+  "service",
+  "billable",
+  "description",
+  "time_since",
+  "time_until",
+  "duration_seconds",
+  "duration",
+];
+
+#[derive(Debug, Serialize)]
+struct ExportRow {
+  id: u32,
+  customer: String,
+  project: Option<String>,
+  service: Option<String>,
+  billable: String,
+  description: Option<String>,
+  time_since: String,
+  time_until: Option<String>,
+  duration_seconds: i64,
+  duration: String,
+}
+
+impl ExportRow {
+  fn new(
+    resources: &mut ResourceCache,
+    entry: TimeEntry,
+  ) -> Result<Self, CliError> {
+    let names = resources.entry_names(&entry)?;
+    let duration = entry.elapsed()?;
+
+    Ok(ExportRow {
+      id: entry.id,
+      customer: names.customer,
+      project: names.project,
+      service: names.service,
+      billable: entry.billable.to_string(),
+      time_since: format_time(&entry.time_since)?,
+      time_until: entry.time_until.as_deref().map(format_time).transpose()?,
+      description: entry.text,
+      duration_seconds: duration,
+      duration: format_duration_hm(duration),
+    })
+  }
+
+  fn fields(&self) -> [String; 10] {
+    [
+      self.id.to_string(),
+      self.customer.clone(),
+      self.project.clone().unwrap_or_default(),
+      self.service.clone().unwrap_or_default(),
+      self.billable.clone(),
+      self.description.clone().unwrap_or_default(),
+      self.time_since.clone(),
+      self.time_until.clone().unwrap_or_default(),
+      self.duration_seconds.to_string(),
+      self.duration.clone(),
+    ]
+  }
+}
+
+fn csv_field(field: &str) -> String {
+  if field.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", field.replace('"', "\"\""))
+  } else {
+    field.to_string()
+  }
+}
+
+fn markdown_field(field: &str) -> String {
+  field
+    .replace('|', "\\|")
+    .replace("\r\n", " ")
+    .replace('\n', " ")
+}
+
+fn write_rows(
+  out: &mut impl Write,
+  rows: &[ExportRow],
+  format: ExportFormat,
+) -> std::io::Result<()> {
+  match format {
+    ExportFormat::Csv => {
+      writeln!(out, "{}", HEADERS.join(","))?;
+      for row in rows {
+        let fields = row.fields().map(|field| csv_field(&field));
+        writeln!(out, "{}", fields.join(","))?;
+      }
+    }
+    ExportFormat::Markdown => {
+      writeln!(out, "| {} |", HEADERS.join(" | "))?;
+      writeln!(out, "|{}", "---|".repeat(HEADERS.len()))?;
+      for row in rows {
+        let fields = row.fields().map(|field| markdown_field(&field));
+        writeln!(out, "| {} |", fields.join(" | "))?;
+      }
+    }
+    ExportFormat::Json => {
+      writeln!(out, "{}", serde_json::to_string(rows).unwrap())?;
+    }
+    ExportFormat::Ndjson => {
+      for row in rows {
+        writeln!(out, "{}", serde_json::to_string(row).unwrap())?;
+      }
+    }
+    ExportFormat::Yaml => {
+      write!(out, "{}", serde_yaml::to_string(rows).unwrap())?
+    }
+    ExportFormat::Table | ExportFormat::Tsv => {
+      let fields: Vec<Vec<String>> =
+        rows.iter().map(|row| row.fields().to_vec()).collect();
+      match format {
+        ExportFormat::Table => write_table(out, &HEADERS, &fields)?,
+        _ => write_tsv(out, &HEADERS, &fields)?,
+      }
+    }
+  }
+
+  Ok(())
+}
+
+pub fn export(
+  range: ReportRange,
+  users: EntryUsers,
+  format: ExportFormat,
+) -> Result<(), CliError> {
+  let config = Config::read()?;
+  let api_client = config.api_client()?;
+  let mut resources = ResourceCache::new(&api_client, &config);
+
+  let (from, to) = range.resolve()?;
+  let entries = api_client.list_entries(
+    &to_api_time(from),
+    &to_api_time(to),
+    users.users_id(&api_client)?,
+  )?;
+  let rows = entries
+    .into_iter()
+    .map(|entry| ExportRow::new(&mut resources, entry))
+    .collect::<Result<Vec<_>, _>>()?;
+
+  // The output is usually piped into other tools, which may stop reading
+  // early
+  match write_rows(&mut std::io::stdout().lock(), &rows, format) {
+    Err(err) if err.kind() != ErrorKind::BrokenPipe => Err(err.into()),
+    _ => Ok(()),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn plain_csv_fields_are_unquoted() {
+    assert_eq!(csv_field("ACME GmbH"), "ACME GmbH");
+    assert_eq!(csv_field(""), "");
+  }
+
+  #[test]
+  fn csv_fields_with_separators_are_quoted() {
+    assert_eq!(csv_field("Smith, John"), "\"Smith, John\"");
+    assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+    assert_eq!(csv_field("line\r\nbreak"), "\"line\r\nbreak\"");
+  }
+
+  #[test]
+  fn csv_quotes_are_doubled() {
+    assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+  }
+
+  #[test]
+  fn markdown_fields_escape_pipes_and_newlines() {
+    assert_eq!(markdown_field("a|b"), "a\\|b");
+    assert_eq!(markdown_field("a\r\nb\nc"), "a b c");
+  }
+}
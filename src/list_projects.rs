@@ -1,33 +1,22 @@
 use std::collections::HashMap;
 
+use serde::Serialize;
+
 use crate::{
-  api::{client::ApiClient, Customer, CustomersFilter, Project},
+  api::{Customer, Project},
+  cache::ResourceCache,
   config::Config,
-  Args, CliError,
+  output::{OutputFormat, Render},
+  CliError,
 };
 
-pub fn list_projects(args: &Args) -> Result<(), CliError> {
-  let config = Config::read()?;
-  let api_client = ApiClient::new(&config.api_user, &config.api_key);
-
-  let mut projects: HashMap<String, Vec<String>> = HashMap::new();
-  let customers = api_client.list_resources::<Customer>()?;
-  for customer in customers {
-    let customer_projects = api_client
-      .list_resources_filtered::<Project, CustomersFilter>(customer.id)?;
-    projects.insert(
-      customer.name,
-      customer_projects
-        .into_iter()
-        .map(|project| project.name)
-        .collect(),
-    );
-  }
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+struct ProjectsOutput(HashMap<String, Vec<String>>);
 
-  if args.json {
-    println!("{}", serde_json::to_string(&projects).unwrap());
-  } else {
-    for (customer, projects) in projects {
+impl Render for ProjectsOutput {
+  fn print_human(&self) {
+    for (customer, projects) in &self.0 {
       println!("{}: ", customer);
       if projects.is_empty() {
         println!("  [no projects]");
@@ -38,5 +27,44 @@ pub fn list_projects(args: &Args) -> Result<(), CliError> {
     }
   }
 
+  fn headers(&self) -> Vec<&str> {
+    vec!["customer", "project"]
+  }
+
+  fn rows(&self) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    for (customer, projects) in &self.0 {
+      if projects.is_empty() {
+        rows.push(vec![customer.clone(), String::new()]);
+      }
+      for project in projects {
+        rows.push(vec![customer.clone(), project.clone()]);
+      }
+    }
+    rows
+  }
+}
+
+pub fn list_projects(format: OutputFormat) -> Result<(), CliError> {
+  let config = Config::read()?;
+  let api_client = config.api_client()?;
+  let mut resources = ResourceCache::new(&api_client, &config);
+
+  let mut projects: HashMap<String, Vec<String>> = HashMap::new();
+  let customers = resources.list::<Customer>()?.to_vec();
+  let all_projects = resources.list::<Project>()?;
+  for customer in customers {
+    projects.insert(
+      customer.name,
+      all_projects
+        .iter()
+        .filter(|project| project.customers_id == customer.id)
+        .map(|project| project.name.clone())
+        .collect(),
+    );
+  }
+
+  ProjectsOutput(projects).render(format);
+
   Ok(())
 }
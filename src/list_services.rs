@@ -1,29 +1,58 @@
+use serde::Serialize;
+
 use crate::{
-  api::{client::ApiClient, Service},
+  api::Service,
+  cache::ResourceCache,
   config::Config,
+  output::{OutputFormat, Render},
   CliError,
 };
 
-pub fn list_services(json: bool) -> Result<(), CliError> {
-  let config = Config::read()?;
-  let api_client = ApiClient::new(&config.api_user, &config.api_key);
-
-  let services = api_client.list_resources::<Service>()?;
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+struct ServicesOutput(Vec<Service>);
 
-  if json {
-    println!("{}", serde_json::to_string(&services).unwrap());
-  } else {
-    for service in services {
+impl Render for ServicesOutput {
+  fn print_human(&self) {
+    for service in &self.0 {
       print!("{}", service.name);
       if !service.active {
-        print!(" {}", "(inactive)");
+        print!(" (inactive)");
       }
-      if let Some(note) = service.note {
+      if let Some(note) = &service.note {
         print!(" \t\tNote: {note}");
       }
-      println!("");
+      println!();
     }
   }
 
+  fn headers(&self) -> Vec<&str> {
+    vec!["id", "name", "active", "note"]
+  }
+
+  fn rows(&self) -> Vec<Vec<String>> {
+    self
+      .0
+      .iter()
+      .map(|service| {
+        vec![
+          service.id.to_string(),
+          service.name.clone(),
+          service.active.to_string(),
+          service.note.clone().unwrap_or_default(),
+        ]
+      })
+      .collect()
+  }
+}
+
+pub fn list_services(format: OutputFormat) -> Result<(), CliError> {
+  let config = Config::read()?;
+  let api_client = config.api_client()?;
+  let mut resources = ResourceCache::new(&api_client, &config);
+
+  let services = resources.list::<Service>()?.to_vec();
+  ServicesOutput(services).render(format);
+
   Ok(())
 }
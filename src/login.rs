@@ -1,12 +1,73 @@
 use std::io::Write;
 
-use crate::{config::Config, CliError};
+use serde::Serialize;
+use serde_json::json;
 
+use crate::{
+  api::client::ApiClient,
+  cache::ResourceCache,
+  config::{Config, ConfigError},
+  credentials::{keyring_delete, CredentialBackend},
+  output::{Message, OutputFormat, Render},
+  state::ClockState,
+  CliError,
+};
+
+#[derive(Debug, Serialize)]
+struct WhoamiOutput {
+  profile: String,
+  api_user: String,
+  api_url: String,
+  user_id: u32,
+  name: String,
+  email: Option<String>,
+  company: String,
+}
+
+impl Render for WhoamiOutput {
+  fn print_human(&self) {
+    match &self.email {
+      Some(email) => println!("{} <{}>", self.name, email),
+      None => println!("{}", self.name),
+    }
+    println!("Company:\t{}", self.company);
+    println!("Profile:\t{}", self.profile);
+    println!("API user:\t{}", self.api_user);
+    println!("API URL:\t{}", self.api_url);
+  }
+
+  fn headers(&self) -> Vec<&str> {
+    vec![
+      "profile", "api_user", "api_url", "user_id", "name", "email", "company",
+    ]
+  }
+
+  fn rows(&self) -> Vec<Vec<String>> {
+    vec![vec![
+      self.profile.clone(),
+      self.api_user.clone(),
+      self.api_url.clone(),
+      self.user_id.to_string(),
+      self.name.clone(),
+      self.email.clone().unwrap_or_default(),
+      self.company.clone(),
+    ]]
+  }
+}
+
+/// Stores the credentials of the selected profile after checking them
+/// against the API.
 pub fn login(
+  format: OutputFormat,
   api_user: Option<String>,
   api_key: Option<String>,
+  backend: Option<CredentialBackend>,
+  api_key_cmd: Option<String>,
 ) -> Result<(), CliError> {
-  let api_user = api_user.unwrap_or_else(|| {
+  // Keep settings like a custom API URL when logging in again
+  let mut config = Config::read_or_create()?;
+
+  config.api_user = api_user.unwrap_or_else(|| {
     print!("API user: ");
     std::io::stdout().flush().unwrap();
     let mut input = String::new();
@@ -14,19 +75,88 @@ pub fn login(
     input.trim().to_string()
   });
 
-  let api_key = api_key.unwrap_or_else(|| {
-    print!("API key: ");
-    std::io::stdout().flush().unwrap();
-    let input = rpassword::read_password().unwrap();
-    input.trim().to_string()
-  });
-  let config = Config { api_user, api_key };
+  if let Some(api_key_cmd) = api_key_cmd {
+    config.api_key_cmd = Some(api_key_cmd);
+    config.credential_backend = Some(CredentialBackend::Command);
+  } else if backend.is_some() {
+    config.credential_backend = backend;
+  }
+
+  let api_key = match config.credential_backend() {
+    CredentialBackend::Command => config.api_key()?,
+    _ => api_key.unwrap_or_else(|| {
+      print!("API key: ");
+      std::io::stdout().flush().unwrap();
+      let input = rpassword::read_password().unwrap();
+      input.trim().to_string()
+    }),
+  };
+
+  let api_client =
+    ApiClient::new(&config.api_url(), &config.api_user, &api_key)?;
+  let me = api_client.get_current_user()?;
+
+  config.set_api_key(api_key)?;
+  config.write()?;
+
+  Message::new(
+    format!(
+      "Logged in as {} ({}) in profile {}",
+      me.user.name,
+      me.company.name,
+      config.profile_name()
+    ),
+    json!({
+      "profile": config.profile_name(),
+      "name": me.user.name,
+      "company": me.company.name,
+    }),
+  )
+  .render(format);
+
+  Ok(())
+}
+
+/// Removes the credentials of the selected profile, along with its cache.
+pub fn logout(format: OutputFormat) -> Result<(), CliError> {
+  let mut config = Config::read()?;
+  let profile = config.profile_name().to_string();
+  let account = config.account_name();
+  let backend = config.credential_backend();
+
+  config.remove_account()?;
+  if backend == CredentialBackend::Keyring {
+    keyring_delete(&account).map_err(ConfigError::from)?;
+  }
+  ResourceCache::clear(&account)?;
+  ClockState::save(&account, None);
+  config.write()?;
+
+  Message::new(
+    format!("Logged out of profile {profile}."),
+    json!({ "logged_out": true, "profile": profile }),
+  )
+  .render(format);
+
+  Ok(())
+}
+
+/// Shows the user the credentials of the selected profile belong to.
+pub fn whoami(format: OutputFormat) -> Result<(), CliError> {
+  let config = Config::read()?;
+  let api_client = config.api_client()?;
+  let me = api_client.get_current_user()?;
 
-  if let Err(err) = config.write() {
-    eprintln!("Failed to write config: {}", err);
-    std::process::exit(1);
+  WhoamiOutput {
+    profile: config.profile_name().to_string(),
+    api_user: config.api_user.clone(),
+    api_url: config.api_url(),
+    user_id: me.user.id,
+    name: me.user.name,
+    email: me.user.email,
+    company: me.company.name,
   }
+  .render(format);
 
-  println!("Logged in successfully");
   Ok(())
 }
@@ -1,21 +1,63 @@
-use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+use absences::{cancel_absence, list_absences, request_absence};
+use api::{client::ApiError, AbsenceType};
+use balance::balance;
+use bar::BarFormat;
+use cache::{clear_cache, refresh_cache, CacheError};
+use clap::{ArgGroup, CommandFactory, Parser, Subcommand};
+use clap_complete::{ArgValueCandidates, CompleteEnv};
+use completions::{
+  complete_customers, complete_projects, complete_services, completions,
+  CompletionShell, COMPLETE_ENV,
+};
+use config::{Config, Preset};
+use credentials::CredentialBackend;
+use edit::edit;
+use entries::{add_entry, delete_entry, edit_entry, list_entries};
+use export::{export, ExportFormat};
 use list_projects::list_projects;
 use list_services::list_services;
-use login::login;
+use login::{login, logout, whoami};
+use output::OutputFormat;
+use preset::{add_preset, list_presets, remove_preset, PresetError};
+use prompt::{prompt, PromptShell};
+use report::{report, EntryUsers, GroupBy, ReportRange};
 use serde::Serialize;
-use start::{start, StartError};
-use status::status;
+use start::{resume, start, StartError};
+use status::{status, ProgressPeriod};
 use stop::{stop, StopError};
+use switch::switch;
+use template::TemplateError;
 use thiserror::Error;
 
+mod absences;
 mod api;
+mod balance;
+mod bar;
+mod cache;
+mod completions;
 mod config;
+mod credentials;
+mod edit;
+mod entries;
+mod export;
 mod list_projects;
 mod list_services;
 mod login;
+mod matching;
+mod output;
+mod picker;
+mod preset;
+mod prompt;
+mod report;
 mod start;
+mod state;
 mod status;
 mod stop;
+mod switch;
+mod template;
+mod time;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -23,14 +65,38 @@ struct Args {
   #[command(subcommand)]
   command: Command,
 
-  /// Prefer JSON output
-  #[arg(long = "json", short = 'j')]
+  /// The output format
+  #[arg(long = "format", short = 'f', value_enum, default_value_t)]
+  format: OutputFormat,
+
+  /// Shorthand for `--format json`
+  #[arg(long = "json", short = 'j', conflicts_with = "format")]
   json: bool,
+
+  /// The profile to use (defaults to $CLOCKODO_PROFILE, or the default
+  /// profile)
+  #[arg(long = "profile", short = 'P')]
+  profile: Option<String>,
+
+  /// The config file to use (defaults to $CLOCKODO_CONFIG, or config.toml in
+  /// the platform's config directory)
+  #[arg(long = "config", short = 'C', value_name = "PATH")]
+  config: Option<PathBuf>,
+}
+
+impl Args {
+  fn format(&self) -> OutputFormat {
+    if self.json {
+      OutputFormat::Json
+    } else {
+      self.format
+    }
+  }
 }
 
 #[derive(Subcommand, Debug)]
 enum Command {
-  /// Logs the user in
+  /// Logs the user in, checking the credentials against the API
   Login {
     /// The API user
     #[arg(long = "api-user", short = 'u')]
@@ -39,21 +105,65 @@ enum Command {
     /// The API key
     #[arg(long = "api-key", short = 'k')]
     api_key: Option<String>,
+
+    /// Where to store the API key
+    #[arg(long = "backend", value_name = "BACKEND")]
+    backend: Option<CredentialBackend>,
+
+    /// Command printing the API key instead of storing it (implies the
+    /// command backend), e.g. "pass show clockodo"
+    #[arg(
+      long = "api-key-cmd",
+      value_name = "COMMAND",
+      conflicts_with_all = ["api_key", "backend"]
+    )]
+    api_key_cmd: Option<String>,
   },
 
+  /// Removes the stored credentials of the profile
+  Logout,
+
+  /// Shows the user and company the credentials belong to
+  Whoami,
+
   /// Starts the stopclock
   Start {
-    /// The customer name
-    #[arg(long = "customer", short = 'c')]
-    customer: String,
+    /// A preset to take default values from
+    preset: Option<String>,
+
+    /// Resume the most recently stopped time entry (see `resume`)
+    #[arg(
+      long = "last",
+      short = 'l',
+      conflicts_with_all = [
+        "preset", "customer", "project", "service", "billable"
+      ]
+    )]
+    last: bool,
+
+    /// The customer name or `#id`
+    #[arg(
+      long = "customer",
+      short = 'c',
+      add = ArgValueCandidates::new(complete_customers)
+    )]
+    customer: Option<String>,
 
-    /// The project name
-    #[arg(long = "project", short = 'p')]
+    /// The project name or `#id`
+    #[arg(
+      long = "project",
+      short = 'p',
+      add = ArgValueCandidates::new(complete_projects)
+    )]
     project: Option<String>,
 
-    /// The service name
-    #[arg(long = "service", short = 's')]
-    service: String,
+    /// The service name or `#id`
+    #[arg(
+      long = "service",
+      short = 's',
+      add = ArgValueCandidates::new(complete_services)
+    )]
+    service: Option<String>,
 
     /// The billable flag
     #[arg(long = "billable", short = 'b')]
@@ -64,14 +174,170 @@ enum Command {
     description: Option<String>,
   },
 
+  /// Starts a new time entry with the customer, project, service, billable
+  /// flag and description of the most recently stopped one
+  Resume {
+    /// Use a different description
+    #[arg(long = "description", short = 'd')]
+    description: Option<String>,
+  },
+
   /// Stops the stopclock
   Stop,
 
+  /// Stops the running time entry and starts a new one without a gap
+  Switch {
+    /// The customer name or `#id` (defaults to the previous customer)
+    #[arg(
+      long = "customer",
+      short = 'c',
+      add = ArgValueCandidates::new(complete_customers)
+    )]
+    customer: Option<String>,
+
+    /// The project name or `#id` (defaults to the previous project)
+    #[arg(
+      long = "project",
+      short = 'p',
+      add = ArgValueCandidates::new(complete_projects)
+    )]
+    project: Option<String>,
+
+    /// The service name or `#id` (defaults to the previous service)
+    #[arg(
+      long = "service",
+      short = 's',
+      add = ArgValueCandidates::new(complete_services)
+    )]
+    service: Option<String>,
+
+    /// The billable flag (defaults to the previous billable flag)
+    #[arg(long = "billable", short = 'b')]
+    billable: Option<bool>,
+
+    /// The description
+    #[arg(long = "description", short = 'd')]
+    description: Option<String>,
+  },
+
+  /// Edits the currently running time entry
+  #[command(group(ArgGroup::new("changes").required(true).multiple(true)))]
+  Edit {
+    /// The new customer name or `#id`
+    #[arg(
+      long = "customer",
+      short = 'c',
+      group = "changes",
+      add = ArgValueCandidates::new(complete_customers)
+    )]
+    customer: Option<String>,
+
+    /// The new project name or `#id`
+    #[arg(
+      long = "project",
+      short = 'p',
+      group = "changes",
+      add = ArgValueCandidates::new(complete_projects)
+    )]
+    project: Option<String>,
+
+    /// Remove the project (done implicitly when changing the customer)
+    #[arg(long = "no-project", group = "changes", conflicts_with = "project")]
+    no_project: bool,
+
+    /// The new service name or `#id`
+    #[arg(
+      long = "service",
+      short = 's',
+      group = "changes",
+      add = ArgValueCandidates::new(complete_services)
+    )]
+    service: Option<String>,
+
+    /// The new billable flag
+    #[arg(long = "billable", short = 'b', group = "changes")]
+    billable: Option<bool>,
+
+    /// The new description
+    #[arg(long = "description", short = 'd', group = "changes")]
+    description: Option<String>,
+
+    /// The new start time (`HH:MM[:SS]`, `YYYY-MM-DD HH:MM[:SS]` or RFC 3339)
+    #[arg(long = "since", group = "changes")]
+    since: Option<String>,
+  },
+
   /// Shows the current status of the stopclock
+  #[command(group(ArgGroup::new("line").multiple(true)))]
   Status {
     /// Show the duration of the current time entry
     #[arg(long = "duration", short = 'd')]
     duration: bool,
+
+    /// Print a single line using the given template, e.g.
+    /// '{customer} {service} {duration}'. Placeholders are the fields of the
+    /// time entry. Without a value, the `status_template` config option is
+    /// used.
+    #[arg(
+      long = "template",
+      short = 't',
+      value_name = "TEMPLATE",
+      group = "line",
+      conflicts_with = "duration"
+    )]
+    template: Option<Option<String>>,
+
+    /// Print the status in the format of a status bar module. The text is
+    /// rendered using the status template.
+    #[arg(
+      long = "bar",
+      value_name = "BAR",
+      group = "line",
+      conflicts_with = "duration"
+    )]
+    bar: Option<BarFormat>,
+
+    /// Keep printing the status every second, polling the API every SECONDS
+    /// (requires --template or --bar)
+    #[arg(
+      long = "watch",
+      short = 'w',
+      value_name = "SECONDS",
+      num_args = 0..=1,
+      default_missing_value = "30",
+      requires = "line"
+    )]
+    watch: Option<u64>,
+
+    /// Show today's worked hours against the target hours, the remaining
+    /// time and the projected clock-out time
+    #[arg(long = "day", conflicts_with_all = ["duration", "line"])]
+    day: bool,
+
+    /// Show this week's worked hours against the target hours, the remaining
+    /// time and the projected clock-out time
+    #[arg(long = "week", conflicts_with_all = ["duration", "line"])]
+    week: bool,
+  },
+
+  /// Prints a short segment for shell prompts, using only locally stored
+  /// state (no API requests)
+  Prompt {
+    /// Template of the segment (placeholders: id, customer, project,
+    /// service, description, duration)
+    #[arg(long = "template", short = 't', conflicts_with = "init")]
+    template: Option<String>,
+
+    /// Print a snippet integrating the segment into the given shell's prompt
+    #[arg(long = "init", value_name = "SHELL")]
+    init: Option<PromptShell>,
+  },
+
+  /// Prints a shell completion script, e.g. `source <(clockodo-cli
+  /// completions bash)`
+  Completions {
+    /// The shell to generate the script for
+    shell: CompletionShell,
   },
 
   /// Lists available customers/projects
@@ -79,6 +345,366 @@ enum Command {
 
   /// Lists available services
   ListServices,
+
+  /// Shows the tracked time of a period, grouped by customer, project, etc.
+  Report {
+    #[command(flatten)]
+    range: RangeArgs,
+
+    #[command(flatten)]
+    users: UserArgs,
+
+    /// What to group the time entries by
+    #[arg(
+      long = "group-by",
+      short = 'g',
+      value_enum,
+      default_value_t = GroupBy::Customer
+    )]
+    group_by: GroupBy,
+  },
+
+  /// Exports time entries of a period
+  Export {
+    #[command(flatten)]
+    range: RangeArgs,
+
+    #[command(flatten)]
+    users: UserArgs,
+
+    /// The output format (defaults to the global `--format`, with CSV in
+    /// place of the human-readable format)
+    #[arg(long = "format", short = 'f', value_enum)]
+    format: Option<ExportFormat>,
+  },
+
+  /// Manages presets for the start command
+  Preset {
+    #[command(subcommand)]
+    command: PresetCommand,
+  },
+
+  /// Manages the local customer/project/service cache
+  Cache {
+    #[command(subcommand)]
+    command: CacheCommand,
+  },
+
+  /// Manages finished time entries
+  Entries {
+    #[command(subcommand)]
+    command: EntriesCommand,
+  },
+
+  /// Manages absences like vacation and sick days
+  Absences {
+    #[command(subcommand)]
+    command: AbsencesCommand,
+  },
+
+  /// Shows the remaining vacation days, the overtime and the worked vs.
+  /// target hours
+  Balance {
+    /// The year (defaults to the current one)
+    #[arg(long = "year", short = 'y')]
+    year: Option<i32>,
+  },
+}
+
+/// The time range of a report or export
+#[derive(clap::Args, Debug)]
+#[group(id = "range", multiple = true)]
+struct RangeArgs {
+  /// Use today (default)
+  #[arg(long = "today", conflicts_with_all = ["week", "month", "from"])]
+  today: bool,
+
+  /// Use the current week
+  #[arg(long = "week", conflicts_with_all = ["month", "from"])]
+  week: bool,
+
+  /// Use the current month
+  #[arg(long = "month", conflicts_with = "from")]
+  month: bool,
+
+  /// Start of a custom time range
+  #[arg(long = "from")]
+  from: Option<String>,
+
+  /// End of a custom time range (exclusive, defaults to now)
+  #[arg(long = "to", requires = "from")]
+  to: Option<String>,
+}
+
+impl From<RangeArgs> for ReportRange {
+  fn from(args: RangeArgs) -> Self {
+    match args.from {
+      Some(from) => ReportRange::Custom { from, to: args.to },
+      None if args.week => ReportRange::Week,
+      None if args.month => ReportRange::Month,
+      None => ReportRange::Today,
+    }
+  }
+}
+
+/// Whose time entries to include
+#[derive(clap::Args, Debug)]
+struct UserArgs {
+  /// Include the time entries of the user with this ID instead of your own
+  #[arg(long = "user", value_name = "ID", conflicts_with = "all_users")]
+  user: Option<u32>,
+
+  /// Include the time entries of all users visible to you
+  #[arg(long = "all-users")]
+  all_users: bool,
+}
+
+impl From<UserArgs> for EntryUsers {
+  fn from(args: UserArgs) -> Self {
+    match args.user {
+      Some(id) => EntryUsers::User(id),
+      None if args.all_users => EntryUsers::All,
+      None => EntryUsers::Me,
+    }
+  }
+}
+
+#[derive(Subcommand, Debug)]
+enum PresetCommand {
+  /// Adds or replaces a preset
+  Add {
+    /// The preset name
+    name: String,
+
+    /// The customer name or `#id`
+    #[arg(
+      long = "customer",
+      short = 'c',
+      add = ArgValueCandidates::new(complete_customers)
+    )]
+    customer: Option<String>,
+
+    /// The project name or `#id`
+    #[arg(
+      long = "project",
+      short = 'p',
+      add = ArgValueCandidates::new(complete_projects)
+    )]
+    project: Option<String>,
+
+    /// The service name or `#id`
+    #[arg(
+      long = "service",
+      short = 's',
+      add = ArgValueCandidates::new(complete_services)
+    )]
+    service: Option<String>,
+
+    /// The billable flag
+    #[arg(long = "billable", short = 'b')]
+    billable: Option<bool>,
+
+    /// The description
+    #[arg(long = "description", short = 'd')]
+    description: Option<String>,
+  },
+
+  /// Lists all presets
+  List,
+
+  /// Removes a preset
+  Remove {
+    /// The preset name
+    name: String,
+  },
+}
+
+#[derive(Subcommand, Debug)]
+enum CacheCommand {
+  /// Re-downloads customers, projects and services
+  Refresh,
+
+  /// Removes the cached data
+  Clear,
+}
+
+/// Times accept `YYYY-MM-DD HH:MM[:SS]`, `YYYY-MM-DD`, `HH:MM[:SS]` (today) in
+/// local time, or RFC 3339 timestamps.
+#[derive(Subcommand, Debug)]
+enum EntriesCommand {
+  /// Lists time entries in a time range
+  List {
+    /// Start of the time range (defaults to the start of today)
+    #[arg(long = "since")]
+    since: Option<String>,
+
+    /// End of the time range (defaults to now)
+    #[arg(long = "until")]
+    until: Option<String>,
+
+    #[command(flatten)]
+    users: UserArgs,
+  },
+
+  /// Adds a finished time entry
+  Add {
+    /// The customer name or `#id`
+    #[arg(
+      long = "customer",
+      short = 'c',
+      add = ArgValueCandidates::new(complete_customers)
+    )]
+    customer: String,
+
+    /// The project name or `#id`
+    #[arg(
+      long = "project",
+      short = 'p',
+      add = ArgValueCandidates::new(complete_projects)
+    )]
+    project: Option<String>,
+
+    /// The service name or `#id`
+    #[arg(
+      long = "service",
+      short = 's',
+      add = ArgValueCandidates::new(complete_services)
+    )]
+    service: String,
+
+    /// The billable flag
+    #[arg(long = "billable", short = 'b')]
+    billable: Option<bool>,
+
+    /// The description
+    #[arg(long = "description", short = 'd')]
+    description: Option<String>,
+
+    /// The start time
+    #[arg(long = "since")]
+    since: String,
+
+    /// The end time
+    #[arg(long = "until")]
+    until: String,
+  },
+
+  /// Edits a time entry
+  #[command(group(ArgGroup::new("changes").required(true).multiple(true)))]
+  Edit {
+    /// The ID of the time entry
+    id: u32,
+
+    /// The new customer name or `#id`
+    #[arg(
+      long = "customer",
+      short = 'c',
+      group = "changes",
+      add = ArgValueCandidates::new(complete_customers)
+    )]
+    customer: Option<String>,
+
+    /// The new project name or `#id`
+    #[arg(
+      long = "project",
+      short = 'p',
+      group = "changes",
+      add = ArgValueCandidates::new(complete_projects)
+    )]
+    project: Option<String>,
+
+    /// Remove the project (done implicitly when changing the customer)
+    #[arg(long = "no-project", group = "changes", conflicts_with = "project")]
+    no_project: bool,
+
+    /// The new service name or `#id`
+    #[arg(
+      long = "service",
+      short = 's',
+      group = "changes",
+      add = ArgValueCandidates::new(complete_services)
+    )]
+    service: Option<String>,
+
+    /// The new billable flag
+    #[arg(long = "billable", short = 'b', group = "changes")]
+    billable: Option<bool>,
+
+    /// The new description
+    #[arg(long = "description", short = 'd', group = "changes")]
+    description: Option<String>,
+
+    /// The new start time
+    #[arg(long = "since", group = "changes")]
+    since: Option<String>,
+
+    /// The new end time
+    #[arg(long = "until", group = "changes")]
+    until: Option<String>,
+  },
+
+  /// Deletes a time entry
+  Delete {
+    /// The ID of the time entry
+    id: u32,
+  },
+}
+
+/// Dates are given as `YYYY-MM-DD`.
+#[derive(Subcommand, Debug)]
+enum AbsencesCommand {
+  /// Lists absences overlapping a date range
+  List {
+    /// First day of the range (defaults to the start of the current year)
+    #[arg(long = "since")]
+    since: Option<String>,
+
+    /// Last day of the range (defaults to the end of the first day's year)
+    #[arg(long = "until")]
+    until: Option<String>,
+
+    /// Only list the absences of the user with this ID
+    #[arg(long = "user", value_name = "ID")]
+    user: Option<u32>,
+
+    /// Include declined and cancelled absences
+    #[arg(long = "all", short = 'a')]
+    all: bool,
+  },
+
+  /// Requests an absence
+  Request {
+    /// The kind of absence
+    #[arg(
+      long = "type",
+      short = 't',
+      value_name = "TYPE",
+      default_value = "vacation"
+    )]
+    absence_type: AbsenceType,
+
+    /// The first day
+    #[arg(long = "since")]
+    since: String,
+
+    /// The last day (defaults to the first day)
+    #[arg(long = "until")]
+    until: Option<String>,
+
+    /// Request half a day only
+    #[arg(long = "half-day", conflicts_with = "until")]
+    half_day: bool,
+
+    /// A note for the approver
+    #[arg(long = "note", short = 'n')]
+    note: Option<String>,
+  },
+
+  /// Cancels an absence request
+  Cancel {
+    /// The ID of the absence
+    id: u32,
+  },
 }
 
 #[derive(Debug, Error)]
@@ -87,56 +713,283 @@ enum CliError {
   Config(#[from] config::ConfigError),
 
   #[error("API request failed: {0}")]
-  Api(#[from] reqwest::Error),
+  Api(#[from] ApiError),
+
+  #[error("Cache error: {0}")]
+  Cache(#[from] CacheError),
+
+  #[error("IO error: {0}")]
+  Io(#[from] std::io::Error),
 
   #[error("Failed to parse time: {0}")]
   Time(#[from] chrono::ParseError),
 
+  #[error(transparent)]
+  Preset(#[from] PresetError),
+
   #[error(transparent)]
   Start(#[from] StartError),
 
   #[error(transparent)]
   Stop(#[from] StopError),
+
+  #[error(transparent)]
+  Template(#[from] TemplateError),
+
+  #[error("Interactive prompt failed: {0}")]
+  Prompt(#[from] dialoguer::Error),
+}
+
+impl CliError {
+  /// The HTTP status code of a failed API request behind the error, if any.
+  fn status_code(&self) -> Option<u16> {
+    match self {
+      CliError::Api(err) => err.status().map(|status| status.as_u16()),
+      CliError::Start(StartError::StartAfterStopFailed { reason, .. }) => {
+        reason.status_code()
+      }
+      _ => None,
+    }
+  }
 }
 
 #[derive(Debug, Serialize)]
-struct JsonError {
+struct ErrorOutput {
   error_message: String,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  status_code: Option<u16>,
 }
 
 fn main() {
+  CompleteEnv::with_factory(Args::command)
+    .var(COMPLETE_ENV)
+    .complete();
+
   let args = Args::parse();
+  let format = args.format();
+  if let Some(profile) = args.profile {
+    Config::select_profile(profile);
+  }
+  if let Some(path) = args.config {
+    Config::select_path(path);
+  }
 
   let result = match args.command {
-    Command::Login { api_user, api_key } => login(api_user, api_key),
-    Command::Status { duration } => status(&args, duration),
-    Command::ListProjects => list_projects(&args),
-    Command::ListServices => list_services(args.json),
+    Command::Login {
+      api_user,
+      api_key,
+      backend,
+      api_key_cmd,
+    } => login(format, api_user, api_key, backend, api_key_cmd),
+    Command::Logout => logout(format),
+    Command::Whoami => whoami(format),
+    Command::Status {
+      duration,
+      template,
+      bar,
+      watch,
+      day,
+      week,
+    } => {
+      let progress: Vec<ProgressPeriod> =
+        [(day, ProgressPeriod::Day), (week, ProgressPeriod::Week)]
+          .into_iter()
+          .filter_map(|(selected, period)| selected.then_some(period))
+          .collect();
+      status(format, duration, template, bar, watch, &progress)
+    }
+    Command::Prompt { template, init } => prompt(template.as_deref(), init),
+    Command::Completions { shell } => completions(shell),
+    Command::ListProjects => list_projects(format),
+    Command::ListServices => list_services(format),
     Command::Start {
+      last: true,
+      description,
+      ..
+    } => resume(format, description.as_deref()),
+    Command::Start {
+      preset,
       customer,
       project,
       service,
       billable,
       description,
+      ..
     } => start(
-      args.json,
-      &customer,
-      &service,
+      format,
+      preset.as_deref(),
+      Preset {
+        customer,
+        project,
+        service,
+        billable,
+        description,
+      },
+    ),
+    Command::Resume { description } => resume(format, description.as_deref()),
+    Command::Stop => stop(format),
+    Command::Switch {
+      customer,
+      project,
+      service,
+      billable,
+      description,
+    } => switch(
+      format,
+      customer.as_deref(),
+      service.as_deref(),
+      project.as_deref(),
+      billable,
+      description.as_deref(),
+    ),
+    Command::Edit {
+      customer,
+      project,
+      no_project,
+      service,
+      billable,
+      description,
+      since,
+    } => edit(
+      format,
+      customer.as_deref(),
+      service.as_deref(),
       project.as_deref(),
+      no_project,
       billable,
       description.as_deref(),
+      since.as_deref(),
+    ),
+    Command::Report {
+      range,
+      users,
+      group_by,
+    } => report(format, range.into(), users.into(), group_by),
+    Command::Export {
+      range,
+      users,
+      format: export_format,
+    } => export(
+      range.into(),
+      users.into(),
+      export_format.unwrap_or(format.into()),
     ),
-    Command::Stop => stop(args.json),
+    Command::Preset { command } => match command {
+      PresetCommand::Add {
+        name,
+        customer,
+        project,
+        service,
+        billable,
+        description,
+      } => add_preset(
+        format,
+        name,
+        Preset {
+          customer,
+          project,
+          service,
+          billable,
+          description,
+        },
+      ),
+      PresetCommand::List => list_presets(format),
+      PresetCommand::Remove { name } => remove_preset(format, &name),
+    },
+    Command::Cache { command } => match command {
+      CacheCommand::Refresh => refresh_cache(format),
+      CacheCommand::Clear => clear_cache(format),
+    },
+    Command::Entries { command } => match command {
+      EntriesCommand::List {
+        since,
+        until,
+        users,
+      } => {
+        list_entries(format, since.as_deref(), until.as_deref(), users.into())
+      }
+      EntriesCommand::Add {
+        customer,
+        project,
+        service,
+        billable,
+        description,
+        since,
+        until,
+      } => add_entry(
+        format,
+        &customer,
+        &service,
+        project.as_deref(),
+        billable,
+        description.as_deref(),
+        &since,
+        &until,
+      ),
+      EntriesCommand::Edit {
+        id,
+        customer,
+        project,
+        no_project,
+        service,
+        billable,
+        description,
+        since,
+        until,
+      } => edit_entry(
+        format,
+        id,
+        customer.as_deref(),
+        service.as_deref(),
+        project.as_deref(),
+        no_project,
+        billable,
+        description.as_deref(),
+        since.as_deref(),
+        until.as_deref(),
+      ),
+      EntriesCommand::Delete { id } => delete_entry(format, id),
+    },
+    Command::Absences { command } => match command {
+      AbsencesCommand::List {
+        since,
+        until,
+        user,
+        all,
+      } => list_absences(format, since.as_deref(), until.as_deref(), user, all),
+      AbsencesCommand::Request {
+        absence_type,
+        since,
+        until,
+        half_day,
+        note,
+      } => request_absence(
+        format,
+        absence_type,
+        &since,
+        until.as_deref(),
+        half_day,
+        note.as_deref(),
+      ),
+      AbsencesCommand::Cancel { id } => cancel_absence(format, id),
+    },
+    Command::Balance { year } => balance(format, year),
   };
 
   if let Err(err) = result {
-    if args.json {
-      let json_error = JsonError {
-        error_message: err.to_string(),
-      };
-      println!("{}", serde_json::to_string(&json_error).unwrap());
-    } else {
-      eprintln!("Error: {}", err);
+    let error_output = ErrorOutput {
+      error_message: err.to_string(),
+      status_code: err.status_code(),
+    };
+    match format {
+      OutputFormat::Json => {
+        println!("{}", serde_json::to_string(&error_output).unwrap())
+      }
+      OutputFormat::Yaml => {
+        print!("{}", serde_yaml::to_string(&error_output).unwrap())
+      }
+      _ => eprintln!("Error: {}", err),
     }
     std::process::exit(1);
   }
@@ -0,0 +1,134 @@
+use crate::api::client::Resource;
+
+/// Result of matching a user-supplied name against a list of resources.
+#[derive(Debug)]
+pub enum Match<T> {
+  Found(T),
+  Ambiguous(Vec<T>),
+  NotFound,
+}
+
+impl<T> Match<T> {
+  pub fn map<U>(self, f: impl Fn(T) -> U) -> Match<U> {
+    match self {
+      Match::Found(item) => Match::Found(f(item)),
+      Match::Ambiguous(items) => {
+        Match::Ambiguous(items.into_iter().map(f).collect())
+      }
+      Match::NotFound => Match::NotFound,
+    }
+  }
+}
+
+/// Matches `query` against the resource names. An exact match wins, followed
+/// by case-insensitive equality, prefix and substring matches. The first of
+/// these that yields any candidates decides the result.
+pub fn match_by_name<'a, R: Resource>(
+  resources: &'a [R],
+  query: &str,
+) -> Match<&'a R> {
+  if let Some(resource) = resources.iter().find(|r| r.name() == query) {
+    return Match::Found(resource);
+  }
+
+  let query = query.to_lowercase();
+  let tiers: [&dyn Fn(&str) -> bool; 3] = [
+    &|name| name == query,
+    &|name| name.starts_with(&query),
+    &|name| name.contains(&query),
+  ];
+
+  for matches in tiers {
+    let mut candidates: Vec<&R> = resources
+      .iter()
+      .filter(|r| matches(&r.name().to_lowercase()))
+      .collect();
+    match candidates.len() {
+      0 => continue,
+      1 => return Match::Found(candidates.remove(0)),
+      _ => return Match::Ambiguous(candidates),
+    }
+  }
+
+  Match::NotFound
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::api::Customer;
+
+  fn customers(names: &[&str]) -> Vec<Customer> {
+    names
+      .iter()
+      .enumerate()
+      .map(|(i, name)| Customer {
+        id: i as u32 + 1,
+        name: name.to_string(),
+      })
+      .collect()
+  }
+
+  fn found_id(found: Match<&Customer>) -> u32 {
+    match found {
+      Match::Found(customer) => customer.id,
+      found => panic!("expected a single match, got {found:?}"),
+    }
+  }
+
+  fn ambiguous_ids(found: Match<&Customer>) -> Vec<u32> {
+    match found {
+      Match::Ambiguous(customers) => customers.iter().map(|c| c.id).collect(),
+      found => panic!("expected an ambiguous match, got {found:?}"),
+    }
+  }
+
+  #[test]
+  fn exact_match_wins_over_case_insensitive_match() {
+    let customers = customers(&["acme", "ACME"]);
+    assert_eq!(found_id(match_by_name(&customers, "ACME")), 2);
+  }
+
+  #[test]
+  fn case_insensitive_match_wins_over_prefix_match() {
+    let customers = customers(&["ACME GmbH", "Acme"]);
+    assert_eq!(found_id(match_by_name(&customers, "acme")), 2);
+  }
+
+  #[test]
+  fn prefix_match_wins_over_substring_match() {
+    let customers = customers(&["Big Acme", "Acme GmbH"]);
+    assert_eq!(found_id(match_by_name(&customers, "acm")), 2);
+  }
+
+  #[test]
+  fn substring_match() {
+    let customers = customers(&["ACME GmbH", "Initech"]);
+    assert_eq!(found_id(match_by_name(&customers, "gmbh")), 1);
+  }
+
+  #[test]
+  fn several_candidates_in_a_tier_are_ambiguous() {
+    let customers = customers(&["Acme North", "Acme South", "Initech"]);
+    assert_eq!(ambiguous_ids(match_by_name(&customers, "acme")), [1, 2]);
+  }
+
+  #[test]
+  fn duplicate_names_are_ambiguous() {
+    let customers = customers(&["Acme", "acme"]);
+    assert_eq!(ambiguous_ids(match_by_name(&customers, "ACME")), [1, 2]);
+  }
+
+  #[test]
+  fn no_candidates() {
+    let customers = customers(&["ACME GmbH"]);
+    assert!(matches!(
+      match_by_name(&customers, "initech"),
+      Match::NotFound
+    ));
+    assert!(matches!(
+      match_by_name::<Customer>(&[], "acme"),
+      Match::NotFound
+    ));
+  }
+}
@@ -0,0 +1,151 @@
+use std::io::Write;
+
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+  /// Human-readable text
+  #[default]
+  Human,
+  Json,
+  Yaml,
+  /// Aligned columns
+  Table,
+  /// Tab-separated values
+  Tsv,
+}
+
+/// Command output that can be printed in every [`OutputFormat`].
+pub trait Render: Serialize {
+  /// Prints the human-readable representation.
+  fn print_human(&self);
+
+  /// Column headers of the tabular representation.
+  fn headers(&self) -> Vec<&str>;
+
+  /// Rows of the tabular representation.
+  fn rows(&self) -> Vec<Vec<String>>;
+
+  fn render(&self, format: OutputFormat) {
+    match format {
+      OutputFormat::Human => self.print_human(),
+      OutputFormat::Json => {
+        println!("{}", serde_json::to_string(self).unwrap())
+      }
+      OutputFormat::Yaml => print!("{}", serde_yaml::to_string(self).unwrap()),
+      // Write errors (e.g. a pipe closed by `head`) are ignored
+      OutputFormat::Table => {
+        let _ = write_table(
+          &mut std::io::stdout().lock(),
+          &self.headers(),
+          &self.rows(),
+        );
+      }
+      OutputFormat::Tsv => {
+        let _ = write_tsv(
+          &mut std::io::stdout().lock(),
+          &self.headers(),
+          &self.rows(),
+        );
+      }
+    }
+  }
+}
+
+pub fn write_table(
+  out: &mut impl Write,
+  headers: &[&str],
+  rows: &[Vec<String>],
+) -> std::io::Result<()> {
+  let mut widths: Vec<usize> = headers
+    .iter()
+    .map(|header| header.chars().count())
+    .collect();
+  for row in rows {
+    for (width, field) in widths.iter_mut().zip(row) {
+      *width = (*width).max(field.chars().count());
+    }
+  }
+
+  let line = |fields: Vec<&str>| {
+    fields
+      .iter()
+      .zip(&widths)
+      .map(|(field, width)| format!("{:<width$}", field, width = width))
+      .collect::<Vec<_>>()
+      .join("  ")
+      .trim_end()
+      .to_string()
+  };
+
+  writeln!(out, "{}", line(headers.to_vec()))?;
+  for row in rows {
+    writeln!(out, "{}", line(row.iter().map(String::as_str).collect()))?;
+  }
+
+  Ok(())
+}
+
+pub fn write_tsv(
+  out: &mut impl Write,
+  headers: &[&str],
+  rows: &[Vec<String>],
+) -> std::io::Result<()> {
+  let escape = |field: &str| field.replace(['\t', '\n', '\r'], " ");
+
+  writeln!(out, "{}", headers.join("\t"))?;
+  for row in rows {
+    let fields: Vec<String> = row.iter().map(|field| escape(field)).collect();
+    writeln!(out, "{}", fields.join("\t"))?;
+  }
+
+  Ok(())
+}
+
+/// A confirmation message. The human-readable representation is the message,
+/// all other formats show the attached data.
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub struct Message {
+  #[serde(skip)]
+  text: String,
+  data: Value,
+}
+
+impl Message {
+  /// Creates a message; `data` is expected to be a flat JSON object.
+  pub fn new(text: impl Into<String>, data: Value) -> Self {
+    Message {
+      text: text.into(),
+      data,
+    }
+  }
+}
+
+impl Render for Message {
+  fn print_human(&self) {
+    println!("{}", self.text);
+  }
+
+  fn headers(&self) -> Vec<&str> {
+    match &self.data {
+      Value::Object(data) => data.keys().map(String::as_str).collect(),
+      _ => vec!["value"],
+    }
+  }
+
+  fn rows(&self) -> Vec<Vec<String>> {
+    let field = |value: &Value| match value {
+      Value::String(value) => value.clone(),
+      Value::Null => String::new(),
+      value => value.to_string(),
+    };
+
+    match &self.data {
+      Value::Object(data) => vec![data.values().map(field).collect()],
+      value => vec![vec![field(value)]],
+    }
+  }
+}
@@ -0,0 +1,179 @@
+use std::io::IsTerminal;
+
+use dialoguer::{theme::ColorfulTheme, Confirm, FuzzySelect, Input};
+
+use crate::{
+  api::{client::Resource, Customer, Service},
+  cache::ResourceCache,
+  config::Preset,
+  start::StartError,
+  CliError,
+};
+
+/// Whether the user can be prompted for missing arguments.
+pub fn is_interactive() -> bool {
+  std::io::stdin().is_terminal() && std::io::stderr().is_terminal()
+}
+
+/// Prompts for the `start` arguments missing from `args`: customer, project,
+/// service, billable flag and description. Afterwards, the equivalent
+/// non-interactive command is printed.
+pub fn pick_start_args(
+  resources: &mut ResourceCache,
+  args: Preset,
+) -> Result<Preset, CliError> {
+  let theme = ColorfulTheme::default();
+
+  // A project is only asked for together with the customer, as the project
+  // list depends on it
+  let (customer, customers_id) = match args.customer {
+    Some(customer) => (customer, None),
+    None => {
+      let customers = resources.list::<Customer>()?.to_vec();
+      let customer = select(&theme, "Customer", &customers)?;
+      (selector(&customers, customer), Some(customer.id))
+    }
+  };
+  let project = match (args.project, customers_id) {
+    (Some(project), _) => Some(project),
+    (None, Some(customers_id)) => {
+      pick_project(&theme, resources, customers_id)?
+    }
+    (None, None) => None,
+  };
+
+  let service = match args.service {
+    Some(service) => service,
+    None => {
+      let services: Vec<Service> = resources
+        .list::<Service>()?
+        .iter()
+        .filter(|service| service.active)
+        .cloned()
+        .collect();
+      let service = select(&theme, "Service", &services)?;
+      selector(&services, service)
+    }
+  };
+
+  let billable = match args.billable {
+    Some(billable) => billable,
+    None => Confirm::with_theme(&theme)
+      .with_prompt("Billable?")
+      .default(false)
+      .interact_opt()?
+      .ok_or(StartError::Cancelled)?,
+  };
+
+  let description = match args.description {
+    Some(description) => Some(description),
+    None => Some(
+      Input::<String>::with_theme(&theme)
+        .with_prompt("Description")
+        .allow_empty(true)
+        .interact_text()?,
+    )
+    .filter(|description| !description.is_empty()),
+  };
+
+  let picked = Preset {
+    customer: Some(customer),
+    project,
+    service: Some(service),
+    billable: Some(billable),
+    description,
+  };
+  eprintln!("\nEquivalent command: {}\n", start_command(&picked));
+
+  Ok(picked)
+}
+
+fn pick_project(
+  theme: &ColorfulTheme,
+  resources: &mut ResourceCache,
+  customers_id: u32,
+) -> Result<Option<String>, CliError> {
+  let projects = resources.customer_projects(customers_id)?;
+  if projects.is_empty() {
+    return Ok(None);
+  }
+
+  let mut items = vec!["(no project)".to_string()];
+  items.extend(projects.iter().map(|project| project.name.clone()));
+  let index = FuzzySelect::with_theme(theme)
+    .with_prompt("Project")
+    .items(&items)
+    .default(0)
+    .interact_opt()?
+    .ok_or(StartError::Cancelled)?;
+
+  Ok(match index {
+    0 => None,
+    index => Some(selector(&projects, &projects[index - 1])),
+  })
+}
+
+fn select<'a, R: Resource>(
+  theme: &ColorfulTheme,
+  prompt: &str,
+  resources: &'a [R],
+) -> Result<&'a R, CliError> {
+  let names: Vec<&str> = resources.iter().map(R::name).collect();
+  let index = FuzzySelect::with_theme(theme)
+    .with_prompt(prompt)
+    .items(&names)
+    .interact_opt()?
+    .ok_or(StartError::Cancelled)?;
+
+  Ok(&resources[index])
+}
+
+/// Returns the name of a resource, or its `#id` if the name isn't unique.
+fn selector<R: Resource>(resources: &[R], resource: &R) -> String {
+  let duplicates = resources
+    .iter()
+    .filter(|r| r.name() == resource.name())
+    .count();
+  match duplicates {
+    0 | 1 => resource.name().to_string(),
+    _ => format!("#{}", resource.id()),
+  }
+}
+
+/// Formats the `start` command line for the given arguments.
+fn start_command(args: &Preset) -> String {
+  let mut command = String::from("clockodo-cli start");
+  let mut push = |flag: &str, value: &str| {
+    command.push_str(&format!(" {} {}", flag, shell_quote(value)));
+  };
+
+  if let Some(customer) = &args.customer {
+    push("-c", customer);
+  }
+  if let Some(project) = &args.project {
+    push("-p", project);
+  }
+  if let Some(service) = &args.service {
+    push("-s", service);
+  }
+  if let Some(billable) = args.billable {
+    push("-b", &billable.to_string());
+  }
+  if let Some(description) = &args.description {
+    push("-d", description);
+  }
+
+  command
+}
+
+fn shell_quote(value: &str) -> String {
+  let plain = !value.is_empty()
+    && value
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || "#-_.,:/@".contains(c));
+  if plain {
+    value.to_string()
+  } else {
+    format!("'{}'", value.replace('\'', r"'\''"))
+  }
+}
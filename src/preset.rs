@@ -0,0 +1,143 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::json;
+use thiserror::Error;
+
+use crate::{
+  config::{Config, Preset},
+  output::{Message, OutputFormat, Render},
+  CliError,
+};
+
+#[derive(Debug, Error)]
+pub enum PresetError {
+  #[error("Could not find preset {0}")]
+  NotFound(String),
+}
+
+impl Preset {
+  /// Fills fields that are not set with the values of `other`.
+  pub fn or(self, other: &Preset) -> Preset {
+    Preset {
+      customer: self.customer.or_else(|| other.customer.clone()),
+      project: self.project.or_else(|| other.project.clone()),
+      service: self.service.or_else(|| other.service.clone()),
+      billable: self.billable.or(other.billable),
+      description: self.description.or_else(|| other.description.clone()),
+    }
+  }
+
+  fn print_human(&self) {
+    if let Some(customer) = &self.customer {
+      println!("  Customer:\t{}", customer);
+    }
+    if let Some(project) = &self.project {
+      println!("  Project:\t{}", project);
+    }
+    if let Some(service) = &self.service {
+      println!("  Service:\t{}", service);
+    }
+    if let Some(billable) = self.billable {
+      println!("  Billable:\t{}", billable);
+    }
+    if let Some(description) = &self.description {
+      println!("  Description:\t{}", description);
+    }
+  }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+struct PresetsOutput(BTreeMap<String, Preset>);
+
+impl Render for PresetsOutput {
+  fn print_human(&self) {
+    if self.0.is_empty() {
+      println!("No presets defined.");
+    }
+    for (name, preset) in &self.0 {
+      println!("{name}:");
+      preset.print_human();
+    }
+  }
+
+  fn headers(&self) -> Vec<&str> {
+    vec![
+      "name",
+      "customer",
+      "project",
+      "service",
+      "billable",
+      "description",
+    ]
+  }
+
+  fn rows(&self) -> Vec<Vec<String>> {
+    self
+      .0
+      .iter()
+      .map(|(name, preset)| {
+        vec![
+          name.clone(),
+          preset.customer.clone().unwrap_or_default(),
+          preset.project.clone().unwrap_or_default(),
+          preset.service.clone().unwrap_or_default(),
+          preset.billable.map(|b| b.to_string()).unwrap_or_default(),
+          preset.description.clone().unwrap_or_default(),
+        ]
+      })
+      .collect()
+  }
+}
+
+impl Config {
+  pub fn preset(&self, name: &str) -> Result<&Preset, PresetError> {
+    self
+      .presets
+      .get(name)
+      .ok_or_else(|| PresetError::NotFound(name.into()))
+  }
+}
+
+pub fn add_preset(
+  format: OutputFormat,
+  name: String,
+  preset: Preset,
+) -> Result<(), CliError> {
+  let mut config = Config::read()?;
+  config.presets.insert(name.clone(), preset);
+  config.write()?;
+
+  Message::new(
+    format!("Saved preset {name}."),
+    json!({ "saved": true, "name": name }),
+  )
+  .render(format);
+
+  Ok(())
+}
+
+pub fn list_presets(format: OutputFormat) -> Result<(), CliError> {
+  let config = Config::read()?;
+  PresetsOutput(config.presets).render(format);
+
+  Ok(())
+}
+
+pub fn remove_preset(format: OutputFormat, name: &str) -> Result<(), CliError> {
+  let mut config = Config::read()?;
+  config
+    .presets
+    .remove(name)
+    .ok_or_else(|| PresetError::NotFound(name.into()))?;
+  config.write()?;
+
+  Message::new(
+    format!("Removed preset {name}."),
+    json!({ "removed": true, "name": name }),
+  )
+  .render(format);
+
+  Ok(())
+}
@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+
+use crate::{
+  config::Config, state::ClockState, template::render_template,
+  time::format_duration_hm, CliError,
+};
+
+/// Template used by `prompt` if none is given.
+const DEFAULT_PROMPT_TEMPLATE: &str = "{customer} {duration}";
+
+/// Shells (and prompts) `prompt --init` can generate snippets for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PromptShell {
+  Bash,
+  Zsh,
+  Fish,
+  Starship,
+}
+
+const BASH_SNIPPET: &str = r#"__clockodo_prompt() {
+  local segment
+  segment="$(clockodo-cli prompt 2>/dev/null)"
+  [ -n "$segment" ] && printf '[%s] ' "$segment"
+}
+PS1='$(__clockodo_prompt)'"$PS1"
+"#;
+
+const ZSH_SNIPPET: &str = r#"__clockodo_prompt() {
+  local segment
+  segment="$(clockodo-cli prompt 2>/dev/null)"
+  [ -n "$segment" ] && printf '[%s] ' "$segment"
+}
+setopt PROMPT_SUBST
+PROMPT='$(__clockodo_prompt)'"$PROMPT"
+"#;
+
+const FISH_SNIPPET: &str = r#"if not functions -q __clockodo_original_prompt
+  functions -c fish_prompt __clockodo_original_prompt
+end
+function fish_prompt
+  set -l segment (clockodo-cli prompt 2>/dev/null)
+  test -n "$segment"; and printf '[%s] ' $segment
+  __clockodo_original_prompt
+end
+"#;
+
+const STARSHIP_SNIPPET: &str = r#"[custom.clockodo]
+command = "clockodo-cli prompt"
+when = true
+format = "[\\[$output\\]]($style) "
+style = "bold yellow"
+"#;
+
+/// Prints a short prompt segment for the running entry. Only the local state
+/// file is read, so the segment can be out of date if the clock was changed
+/// elsewhere; it is refreshed by every `status` call.
+pub fn prompt(
+  template: Option<&str>,
+  init: Option<PromptShell>,
+) -> Result<(), CliError> {
+  if let Some(shell) = init {
+    print!(
+      "{}",
+      match shell {
+        PromptShell::Bash => BASH_SNIPPET,
+        PromptShell::Zsh => ZSH_SNIPPET,
+        PromptShell::Fish => FISH_SNIPPET,
+        PromptShell::Starship => STARSHIP_SNIPPET,
+      }
+    );
+    return Ok(());
+  }
+
+  // Without a readable config, there's no account to show the state of
+  let state = match Config::read()
+    .ok()
+    .and_then(|config| ClockState::read(&config.account_name()))
+  {
+    Some(state) => state,
+    None => return Ok(()),
+  };
+  let duration = match state.time_since.parse::<DateTime<Utc>>() {
+    Ok(since) => Utc::now().signed_duration_since(since).num_seconds(),
+    Err(_) => 0,
+  };
+
+  let segment =
+    render_template(template.unwrap_or(DEFAULT_PROMPT_TEMPLATE), |name| {
+      match name {
+        "id" => Some(state.id.to_string()),
+        "customer" => Some(state.customer.clone()),
+        "project" => Some(state.project.clone().unwrap_or_default()),
+        "service" => Some(state.service.clone().unwrap_or_default()),
+        "description" => Some(state.description.clone().unwrap_or_default()),
+        "duration" => Some(format_duration_hm(duration)),
+        _ => None,
+      }
+    })?;
+  println!("{}", segment);
+
+  Ok(())
+}
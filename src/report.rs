@@ -0,0 +1,241 @@
+use std::{cmp::Reverse, collections::HashMap};
+
+use chrono::{DateTime, Local, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::{
+  api::{client::ApiClient, Customer, EntryType, Project, Service, TimeEntry},
+  cache::ResourceCache,
+  config::Config,
+  output::{OutputFormat, Render},
+  time::{
+    format_duration, month_range, parse_time, to_api_time, today_range,
+    week_range, DATE_FORMAT,
+  },
+  CliError,
+};
+
+/// What to group the time entries of a report by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+  Customer,
+  Project,
+  Service,
+  Billable,
+  Day,
+}
+
+/// The time range of a report.
+#[derive(Debug)]
+pub enum ReportRange {
+  Today,
+  Week,
+  Month,
+  Custom { from: String, to: Option<String> },
+}
+
+/// Whose time entries to include, the current user's by default.
+#[derive(Debug, Default)]
+pub enum EntryUsers {
+  #[default]
+  Me,
+  User(u32),
+  All,
+}
+
+impl EntryUsers {
+  /// Returns the user ID to filter the time entries by, if any.
+  pub fn users_id(
+    &self,
+    api_client: &ApiClient,
+  ) -> Result<Option<u32>, CliError> {
+    Ok(match self {
+      EntryUsers::Me => Some(api_client.get_current_user()?.user.id),
+      EntryUsers::User(id) => Some(*id),
+      EntryUsers::All => None,
+    })
+  }
+}
+
+#[derive(Debug, Serialize)]
+struct ReportGroup {
+  name: String,
+  entries: usize,
+  seconds: i64,
+  duration: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ReportOutput {
+  from: String,
+  to: String,
+  group_by: GroupBy,
+  groups: Vec<ReportGroup>,
+  total_entries: usize,
+  total_seconds: i64,
+  total_duration: String,
+}
+
+impl ReportRange {
+  pub fn resolve(&self) -> Result<(DateTime<Utc>, DateTime<Utc>), CliError> {
+    Ok(match self {
+      ReportRange::Today => today_range(),
+      ReportRange::Week => week_range(),
+      ReportRange::Month => month_range(),
+      ReportRange::Custom { from, to } => (
+        parse_time(from)?,
+        match to {
+          Some(to) => parse_time(to)?,
+          None => Utc::now(),
+        },
+      ),
+    })
+  }
+}
+
+fn group_name(
+  resources: &mut ResourceCache,
+  group_by: GroupBy,
+  entry: &TimeEntry,
+) -> Result<String, CliError> {
+  Ok(match group_by {
+    GroupBy::Customer => resources.get::<Customer>(entry.customers_id)?.name,
+    GroupBy::Project => match entry.projects_id {
+      Some(id) => resources.get::<Project>(id)?.name,
+      None => "[no project]".to_string(),
+    },
+    GroupBy::Service => match entry.services_id {
+      Some(id) => resources.get::<Service>(id)?.name,
+      None => "[no service]".to_string(),
+    },
+    GroupBy::Billable => entry.billable.to_string(),
+    GroupBy::Day => entry
+      .time_since
+      .parse::<DateTime<Utc>>()?
+      .with_timezone(&Local)
+      .format("%Y-%m-%d")
+      .to_string(),
+  })
+}
+
+impl Render for ReportOutput {
+  fn print_human(&self) {
+    let header = match self.group_by {
+      GroupBy::Customer => "Customer",
+      GroupBy::Project => "Project",
+      GroupBy::Service => "Service",
+      GroupBy::Billable => "Billable",
+      GroupBy::Day => "Day",
+    };
+    let width = self
+      .groups
+      .iter()
+      .map(|group| group.name.chars().count())
+      .chain([header.len(), "Total".len()])
+      .max()
+      .unwrap();
+
+    println!("Report from {} to {}\n", self.from, self.to);
+    println!("{:<width$}  {:>7}  {:>9}", header, "Entries", "Duration");
+    for group in &self.groups {
+      println!(
+        "{:<width$}  {:>7}  {:>9}",
+        group.name, group.entries, group.duration
+      );
+    }
+    println!(
+      "{:<width$}  {:>7}  {:>9}",
+      "Total", self.total_entries, self.total_duration
+    );
+  }
+
+  fn headers(&self) -> Vec<&str> {
+    vec!["group", "entries", "seconds", "duration"]
+  }
+
+  fn rows(&self) -> Vec<Vec<String>> {
+    let mut rows: Vec<Vec<String>> = self
+      .groups
+      .iter()
+      .map(|group| {
+        vec![
+          group.name.clone(),
+          group.entries.to_string(),
+          group.seconds.to_string(),
+          group.duration.clone(),
+        ]
+      })
+      .collect();
+    rows.push(vec![
+      "Total".to_string(),
+      self.total_entries.to_string(),
+      self.total_seconds.to_string(),
+      self.total_duration.clone(),
+    ]);
+    rows
+  }
+}
+
+pub fn report(
+  format: OutputFormat,
+  range: ReportRange,
+  users: EntryUsers,
+  group_by: GroupBy,
+) -> Result<(), CliError> {
+  let config = Config::read()?;
+  let api_client = config.api_client()?;
+  let mut resources = ResourceCache::new(&api_client, &config);
+
+  let (from, to) = range.resolve()?;
+  let entries = api_client.list_entries(
+    &to_api_time(from),
+    &to_api_time(to),
+    users.users_id(&api_client)?,
+  )?;
+
+  let mut groups: HashMap<String, ReportGroup> = HashMap::new();
+  for entry in entries
+    .iter()
+    .filter(|entry| matches!(entry.entry_type, EntryType::TimeEntry))
+  {
+    let name = group_name(&mut resources, group_by, entry)?;
+    let group = groups.entry(name.clone()).or_insert(ReportGroup {
+      name,
+      entries: 0,
+      seconds: 0,
+      duration: String::new(),
+    });
+    group.entries += 1;
+    group.seconds += entry.elapsed()?;
+  }
+
+  let mut groups: Vec<ReportGroup> = groups
+    .into_values()
+    .map(|group| ReportGroup {
+      duration: format_duration(group.seconds),
+      ..group
+    })
+    .collect();
+  match group_by {
+    GroupBy::Day => groups.sort_by(|a, b| a.name.cmp(&b.name)),
+    _ => groups.sort_by_key(|group| Reverse(group.seconds)),
+  }
+
+  let total_entries = groups.iter().map(|group| group.entries).sum();
+  let total_seconds = groups.iter().map(|group| group.seconds).sum();
+  let output = ReportOutput {
+    from: from.with_timezone(&Local).format(DATE_FORMAT).to_string(),
+    to: to.with_timezone(&Local).format(DATE_FORMAT).to_string(),
+    group_by,
+    groups,
+    total_entries,
+    total_seconds,
+    total_duration: format_duration(total_seconds),
+  };
+
+  output.render(format);
+
+  Ok(())
+}
@@ -1,9 +1,16 @@
+use chrono::{Duration, Utc};
 use thiserror::Error;
 
 use crate::{
-  api::{client::ApiClient, Customer, Project, Service},
-  config::Config,
-  status::{JsonOutput, TimeEntryOutput},
+  api::{Customer, EntryType, Project, Service},
+  cache::{CachedResource, ResourceCache},
+  config::{Config, Preset},
+  matching::Match,
+  output::{OutputFormat, Render},
+  picker::{is_interactive, pick_start_args},
+  state::ClockState,
+  status::{StatusOutput, TimeEntryOutput},
+  time::to_api_time,
   CliError,
 };
 
@@ -18,41 +25,86 @@ pub enum StartError {
   #[error("Could not find project {0}")]
   ProjectNotFound(String),
 
+  #[error(
+    "The {kind} name \"{name}\" is ambiguous, candidates are: {}",
+    candidates.join(", ")
+  )]
+  Ambiguous {
+    kind: &'static str,
+    name: String,
+    candidates: Vec<String>,
+  },
+
+  #[error("The project {project} doesn't belong to the customer {customer}")]
+  ProjectNotForCustomer { project: String, customer: String },
+
+  #[error("No customer specified")]
+  MissingCustomer,
+
+  #[error("No service specified")]
+  MissingService,
+
+  #[error("Cancelled")]
+  Cancelled,
+
+  #[error("No stopped time entry found in the last {0} days")]
+  NoPreviousEntry(i64),
+
   #[error(
     "Failed to start the stopclock (API request was successfull, but no time \
      entry seems to be present)"
   )]
   FailedToStart,
+
+  #[error(
+    "Stopped time entry {stopped}, but failed to start the new one: {reason}"
+  )]
+  StartAfterStopFailed {
+    stopped: u32,
+    #[source]
+    reason: Box<CliError>,
+  },
 }
 
+/// How far back `resume` looks for the previous time entry.
+const RESUME_DAYS: i64 = 30;
+
+/// Starts the stopclock. Fields missing from `args` are taken from the given
+/// preset, if any.
 pub fn start(
-  json: bool,
-  customer: &str,
-  service: &str,
-  project: Option<&str>,
-  billable: Option<bool>,
-  description: Option<&str>,
+  format: OutputFormat,
+  preset: Option<&str>,
+  args: Preset,
 ) -> Result<(), CliError> {
   let config = Config::read()?;
-  let api_client = ApiClient::new(&config.api_user, &config.api_key);
-
-  let customer = api_client
-    .find_resource_by_name::<Customer>(customer)?
-    .ok_or(StartError::CustomerNotFound(customer.into()))?;
-
-  let service = api_client
-    .find_resource_by_name::<Service>(service)?
-    .ok_or(StartError::ServiceNotFound(service.into()))?;
-
-  let project = if let Some(project) = project {
-    Some(
-      api_client
-        .find_resource_by_name::<Project>(project)?
-        .ok_or(StartError::ProjectNotFound(project.into()))?,
-    )
+  let api_client = config.api_client()?;
+
+  let mut resources = ResourceCache::new(&api_client, &config);
+
+  let args = match preset {
+    Some(name) => args.or(config.preset(name)?),
+    None => args,
+  };
+  let missing = args.customer.is_none() || args.service.is_none();
+  let args = if missing && is_interactive() {
+    pick_start_args(&mut resources, args)?
   } else {
-    None
+    args
   };
+  let customer = args
+    .customer
+    .as_deref()
+    .ok_or(StartError::MissingCustomer)?;
+  let service = args.service.as_deref().ok_or(StartError::MissingService)?;
+  let project = args.project.as_deref();
+  let billable = args.billable;
+  let description = args.description.as_deref();
+
+  let customer = find_customer(&mut resources, customer)?;
+  let service = find_service(&mut resources, service)?;
+  let project = project
+    .map(|project| find_project(&mut resources, &customer, project))
+    .transpose()?;
 
   let response = api_client.start_clock(
     customer.id,
@@ -60,9 +112,11 @@ pub fn start(
     billable,
     project.as_ref().map(|p| p.id),
     description,
+    None,
   )?;
 
   let entry = response.running.ok_or(StartError::FailedToStart)?;
+  ClockState::update(&mut resources, Some(&entry))?;
   let output = TimeEntryOutput::from_time_entry(
     entry,
     &customer.name,
@@ -70,19 +124,181 @@ pub fn start(
     project.map(|p| p.name.clone()).as_deref(),
   )?;
 
-  if json {
-    println!(
-      "{}",
-      serde_json::to_string(&JsonOutput {
-        running: true,
-        time_entry: Some(output),
-      })
-      .unwrap()
-    );
-  } else {
-    println!("{}", "Started the stopclock.\n");
-    output.print();
+  if format == OutputFormat::Human {
+    println!("Started the stopclock.\n");
+  }
+  StatusOutput {
+    running: true,
+    time_entry: Some(output),
+    progress: Vec::new(),
+  }
+  .render(format);
+
+  Ok(())
+}
+
+/// Starts a new time entry with the same values as the most recently stopped
+/// one. The description can be overridden.
+pub fn resume(
+  format: OutputFormat,
+  description: Option<&str>,
+) -> Result<(), CliError> {
+  let config = Config::read()?;
+  let api_client = config.api_client()?;
+  let mut resources = ResourceCache::new(&api_client, &config);
+
+  let me = api_client.get_current_user()?;
+  let now = Utc::now();
+  let since = now - Duration::days(RESUME_DAYS);
+  let previous = api_client
+    .list_entries(&to_api_time(since), &to_api_time(now), Some(me.user.id))?
+    .into_iter()
+    .filter(|entry| matches!(entry.entry_type, EntryType::TimeEntry))
+    .filter(|entry| entry.time_until.is_some())
+    .max_by(|a, b| a.time_until.cmp(&b.time_until))
+    .ok_or(StartError::NoPreviousEntry(RESUME_DAYS))?;
+
+  let services_id = previous.services_id.ok_or(StartError::MissingService)?;
+  let description = description.or(previous.text.as_deref());
+  let response = api_client.start_clock(
+    previous.customers_id,
+    services_id,
+    Some(previous.billable.is_billable()),
+    previous.projects_id,
+    description,
+    None,
+  )?;
+
+  let entry = response.running.ok_or(StartError::FailedToStart)?;
+  ClockState::update(&mut resources, Some(&entry))?;
+  let output = TimeEntryOutput::resolve(&mut resources, entry)?;
+
+  if format == OutputFormat::Human {
+    println!("Resumed the previous time entry.\n");
   }
+  StatusOutput {
+    running: true,
+    time_entry: Some(output),
+    progress: Vec::new(),
+  }
+  .render(format);
 
   Ok(())
 }
+
+/// Parses an `#123`-style resource ID.
+fn parse_id(name: &str) -> Option<u32> {
+  name.strip_prefix('#')?.parse().ok()
+}
+
+pub fn find_customer(
+  resources: &mut ResourceCache,
+  name: &str,
+) -> Result<Customer, CliError> {
+  find_resource(resources, name, StartError::CustomerNotFound)
+}
+
+pub fn find_service(
+  resources: &mut ResourceCache,
+  name: &str,
+) -> Result<Service, CliError> {
+  find_resource(resources, name, StartError::ServiceNotFound)
+}
+
+/// Finds a project of the given customer by name or ID.
+pub fn find_project(
+  resources: &mut ResourceCache,
+  customer: &Customer,
+  name: &str,
+) -> Result<Project, CliError> {
+  if let Some(id) = parse_id(name) {
+    let project = resources
+      .find_by_id::<Project>(id)?
+      .ok_or_else(|| StartError::ProjectNotFound(name.into()))?;
+    if project.customers_id != customer.id {
+      return Err(
+        StartError::ProjectNotForCustomer {
+          project: project.name,
+          customer: customer.name.clone(),
+        }
+        .into(),
+      );
+    }
+    return Ok(project);
+  }
+
+  let found = resources.match_customer_project(customer.id, name)?;
+  resolve_match(found, name, StartError::ProjectNotFound)
+}
+
+/// Determines the project change when editing a time entry. Returns `None` if
+/// the project is kept and `Some(None)` if it is removed, which happens with
+/// `no_project` or when the customer changes without a new project.
+///
+/// Projects are looked up among the new customer's projects, or among those
+/// of the entry's current customer (returned by `current_customer`) if the
+/// customer isn't changed.
+pub fn find_edited_project(
+  resources: &mut ResourceCache,
+  new_customer: Option<&Customer>,
+  current_customer: impl FnOnce() -> Result<u32, CliError>,
+  name: Option<&str>,
+  no_project: bool,
+) -> Result<Option<Option<Project>>, CliError> {
+  if no_project {
+    return Ok(Some(None));
+  }
+
+  match (new_customer, name) {
+    (Some(customer), Some(name)) => {
+      Ok(Some(Some(find_project(resources, customer, name)?)))
+    }
+    (None, Some(name)) => {
+      let customer = resources.get::<Customer>(current_customer()?)?;
+      Ok(Some(Some(find_project(resources, &customer, name)?)))
+    }
+    (Some(customer), None) if customer.id != current_customer()? => {
+      Ok(Some(None))
+    }
+    _ => Ok(None),
+  }
+}
+
+fn find_resource<R: CachedResource>(
+  resources: &mut ResourceCache,
+  name: &str,
+  not_found: impl FnOnce(String) -> StartError,
+) -> Result<R, CliError> {
+  if let Some(id) = parse_id(name) {
+    return Ok(
+      resources
+        .find_by_id::<R>(id)?
+        .ok_or_else(|| not_found(name.into()))?,
+    );
+  }
+
+  let found = resources.match_name::<R>(name)?;
+  resolve_match(found, name, not_found)
+}
+
+fn resolve_match<R: CachedResource>(
+  found: Match<R>,
+  name: &str,
+  not_found: impl FnOnce(String) -> StartError,
+) -> Result<R, CliError> {
+  match found {
+    Match::Found(resource) => Ok(resource),
+    Match::Ambiguous(candidates) => Err(
+      StartError::Ambiguous {
+        kind: R::SINGLE_NAME,
+        name: name.into(),
+        candidates: candidates
+          .iter()
+          .map(|candidate| candidate.name().to_string())
+          .collect(),
+      }
+      .into(),
+    ),
+    Match::NotFound => Err(not_found(name.into()).into()),
+  }
+}
@@ -0,0 +1,87 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  api::TimeEntry,
+  cache::{account_file_name, ResourceCache},
+  CliError,
+};
+
+/// The running time entry as last seen by `start`, `stop`, `status` etc.,
+/// kept on disk so `prompt` doesn't need to query the API.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClockState {
+  pub id: u32,
+  pub customer: String,
+  pub project: Option<String>,
+  pub service: Option<String>,
+  pub description: Option<String>,
+  /// Start of the entry in API format (UTC)
+  pub time_since: String,
+}
+
+impl ClockState {
+  /// Creates the state of a running entry, resolving the names of the
+  /// referenced customer, service and project.
+  pub fn resolve(
+    resources: &mut ResourceCache,
+    entry: &TimeEntry,
+  ) -> Result<Self, CliError> {
+    let names = resources.entry_names(entry)?;
+    Ok(ClockState {
+      id: entry.id,
+      customer: names.customer,
+      project: names.project,
+      service: names.service,
+      description: entry.text.clone(),
+      time_since: entry.time_since.clone(),
+    })
+  }
+
+  /// The state file of an account. The state is kept per account, like the
+  /// resource cache, so profiles don't overwrite each other's state.
+  fn path(account: &str) -> Option<PathBuf> {
+    Some(
+      dirs::state_dir()
+        .or_else(dirs::cache_dir)?
+        .join("clockodo-cli")
+        .join(format!("state-{}.json", account_file_name(account))),
+    )
+  }
+
+  /// Reads the last known state of an account, `None` if no entry was
+  /// running or the file is missing or unreadable.
+  pub fn read(account: &str) -> Option<Self> {
+    let state = std::fs::read_to_string(Self::path(account)?).ok()?;
+    serde_json::from_str(&state).ok()
+  }
+
+  /// Stores the running entry, or removes the state file if `state` is
+  /// `None`. The state is only a hint for `prompt`, so errors are ignored.
+  pub fn save(account: &str, state: Option<&Self>) {
+    let path = match Self::path(account) {
+      Some(path) => path,
+      None => return,
+    };
+
+    let _ = match state {
+      Some(state) => std::fs::create_dir_all(path.parent().unwrap())
+        .and_then(|_| Ok(serde_json::to_string(state)?))
+        .and_then(|state| std::fs::write(&path, state)),
+      None => std::fs::remove_file(&path),
+    };
+  }
+
+  /// Updates the state from the running entry reported by the API.
+  pub fn update(
+    resources: &mut ResourceCache,
+    running: Option<&TimeEntry>,
+  ) -> Result<(), CliError> {
+    let state = running
+      .map(|entry| Self::resolve(resources, entry))
+      .transpose()?;
+    Self::save(resources.account(), state.as_ref());
+    Ok(())
+  }
+}
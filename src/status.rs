@@ -1,22 +1,106 @@
-use chrono::Local;
-use serde::Serialize;
-use serde_json::json;
+use std::{
+  fmt,
+  io::{ErrorKind, Write},
+  thread,
+  time::{Duration, Instant},
+};
 
-const DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+use chrono::{Days, Local, Utc};
+use serde::Serialize;
 
 use crate::{
-  api::{
-    client::ApiClient, Billable, Customer, EntryType, Project, Service,
-    TimeEntry,
-  },
+  api::{client::ApiClient, Billable, EntryType, TimeEntry},
+  balance::sum_report_days,
+  bar::BarFormat,
+  cache::ResourceCache,
   config::Config,
-  Args, CliError,
+  output::{OutputFormat, Render},
+  state::ClockState,
+  template::render_template,
+  time::{
+    format_duration, format_duration_hm, format_time, to_api_time, today_range,
+    week_range, DATE_FORMAT,
+  },
+  CliError,
 };
 
 #[derive(Debug, Serialize)]
-pub struct JsonOutput {
+pub struct StatusOutput {
   pub running: bool,
   pub time_entry: Option<TimeEntryOutput>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub progress: Vec<ProgressOutput>,
+}
+
+/// A period whose worked hours are compared against the target hours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressPeriod {
+  Day,
+  Week,
+}
+
+impl ProgressPeriod {
+  fn columns(&self) -> [&'static str; 4] {
+    match self {
+      ProgressPeriod::Day => {
+        ["day_worked", "day_target", "day_remaining", "day_clock_out"]
+      }
+      ProgressPeriod::Week => [
+        "week_worked",
+        "week_target",
+        "week_remaining",
+        "week_clock_out",
+      ],
+    }
+  }
+}
+
+/// Worked and target hours of the current day or week.
+#[derive(Debug, Serialize)]
+pub struct ProgressOutput {
+  period: ProgressPeriod,
+  worked_seconds: i64,
+  worked: String,
+  target_seconds: i64,
+  target: String,
+  remaining_seconds: i64,
+  remaining: String,
+  /// When the target is reached if working on from now
+  clock_out: Option<String>,
+}
+
+impl ProgressOutput {
+  fn row(&self) -> Vec<String> {
+    vec![
+      self.worked.clone(),
+      self.target.clone(),
+      self.remaining.clone(),
+      self.clock_out.clone().unwrap_or_default(),
+    ]
+  }
+}
+
+impl fmt::Display for ProgressOutput {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let label = match self.period {
+      ProgressPeriod::Day => "Today:\t\t",
+      ProgressPeriod::Week => "This week:\t",
+    };
+    write!(f, "{}{} of {}", label, self.worked, self.target)?;
+    match &self.clock_out {
+      Some(clock_out) => {
+        writeln!(f, ", {} remaining (until {})", self.remaining, clock_out)
+      }
+      None => writeln!(f, ", done"),
+    }
+  }
+}
+
+#[derive(Debug, Serialize)]
+struct DurationOutput {
+  running: bool,
+  duration: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -30,6 +114,8 @@ pub struct TimeEntryOutput {
   description: String,
   time_insert: String,
   time_since: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  time_until: Option<String>,
   duration: String,
 }
 
@@ -40,6 +126,7 @@ impl TimeEntryOutput {
     service: Option<&str>,
     project: Option<&str>,
   ) -> Result<Self, CliError> {
+    let duration = entry.elapsed()?;
     Ok(TimeEntryOutput {
       id: entry.id,
       customer: customer.into(),
@@ -48,122 +135,357 @@ impl TimeEntryOutput {
       service: service.map(|s| s.into()),
       billable: entry.billable,
       description: entry.text.unwrap_or("[empty]".to_string()),
-      time_insert: entry
-        .time_insert
-        .parse::<chrono::DateTime<chrono::Utc>>()?
-        .with_timezone(&Local)
-        .format(DATE_FORMAT)
-        .to_string(),
-      time_since: entry
-        .time_since
-        .parse::<chrono::DateTime<chrono::Utc>>()?
-        .with_timezone(&Local)
-        .format(DATE_FORMAT)
-        .to_string(),
-      duration: {
-        let time = entry.time_since.parse::<chrono::DateTime<chrono::Utc>>()?;
-        let duration =
-          chrono::Utc::now().signed_duration_since(time).num_seconds();
-        format!(
-          "{:0>2}:{:0>2}:{:0>2}",
-          duration / 3600,
-          duration % 3600 / 60,
-          duration % 60
-        )
-      },
+      time_insert: format_time(&entry.time_insert)?,
+      time_since: format_time(&entry.time_since)?,
+      time_until: entry.time_until.as_deref().map(format_time).transpose()?,
+      duration: format_duration(duration),
     })
   }
 
-  pub fn print(&self) {
-    println!("ID:\t\t{}", self.id);
-    println!("Customer:\t{}", self.customer);
+  /// Converts a time entry, fetching the names of the referenced customer,
+  /// service and project.
+  pub fn resolve(
+    resources: &mut ResourceCache,
+    entry: TimeEntry,
+  ) -> Result<Self, CliError> {
+    let names = resources.entry_names(&entry)?;
+    Self::from_time_entry(
+      entry,
+      &names.customer,
+      names.service.as_deref(),
+      names.project.as_deref(),
+    )
+  }
+
+  pub const HEADERS: [&'static str; 11] = [
+    "id",
+    "customer",
+    "project",
+    "entry_type",
+    "service",
+    "billable",
+    "description",
+    "time_insert",
+    "time_since",
+    "time_until",
+    "duration",
+  ];
+
+  pub fn row(&self) -> Vec<String> {
+    vec![
+      self.id.to_string(),
+      self.customer.clone(),
+      self.project.clone().unwrap_or_default(),
+      self.entry_type.to_string(),
+      self.service.clone().unwrap_or_default(),
+      self.billable.to_string(),
+      self.description.clone(),
+      self.time_insert.clone(),
+      self.time_since.clone(),
+      self.time_until.clone().unwrap_or_default(),
+      self.duration.clone(),
+    ]
+  }
+
+  /// Returns the value of the field with the given name (one of
+  /// [`Self::HEADERS`]), as shown in tables.
+  pub fn field(&self, name: &str) -> Option<String> {
+    Self::HEADERS
+      .iter()
+      .zip(self.row())
+      .find(|(header, _)| **header == name)
+      .map(|(_, value)| value)
+  }
+}
+
+impl fmt::Display for TimeEntryOutput {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "ID:\t\t{}", self.id)?;
+    writeln!(f, "Customer:\t{}", self.customer)?;
     if let Some(project) = &self.project {
-      println!("Project:\t{}", project);
+      writeln!(f, "Project:\t{}", project)?;
     }
-    println!("Entry type:\t{}", self.entry_type);
+    writeln!(f, "Entry type:\t{}", self.entry_type)?;
     if let Some(service) = &self.service {
-      println!("Service:\t{}", service);
+      writeln!(f, "Service:\t{}", service)?;
     }
-    println!("Billable:\t{}", self.billable);
-    println!("Description:\t{}", self.description);
-    println!("Time inserted:\t{}", self.time_insert);
-    println!("Time started:\t{}", self.time_since);
-    println!("Duration:\t{}", self.duration);
+    writeln!(f, "Billable:\t{}", self.billable)?;
+    writeln!(f, "Description:\t{}", self.description)?;
+    writeln!(f, "Time inserted:\t{}", self.time_insert)?;
+    writeln!(f, "Time started:\t{}", self.time_since)?;
+    if let Some(time_until) = &self.time_until {
+      writeln!(f, "Time stopped:\t{}", time_until)?;
+    }
+    writeln!(f, "Duration:\t{}", self.duration)
+  }
+}
+
+impl Render for TimeEntryOutput {
+  fn print_human(&self) {
+    print!("{}", self);
+  }
+
+  fn headers(&self) -> Vec<&str> {
+    Self::HEADERS.to_vec()
+  }
+
+  fn rows(&self) -> Vec<Vec<String>> {
+    vec![self.row()]
   }
 }
 
-pub fn status(args: &Args, duration: bool) -> Result<(), CliError> {
+impl Render for StatusOutput {
+  fn print_human(&self) {
+    match &self.time_entry {
+      Some(time_entry) => time_entry.print_human(),
+      None => println!("No time entry running."),
+    }
+    if !self.progress.is_empty() {
+      println!();
+    }
+    for progress in &self.progress {
+      print!("{}", progress);
+    }
+  }
+
+  fn headers(&self) -> Vec<&str> {
+    let mut headers = vec!["running"];
+    headers.extend(TimeEntryOutput::HEADERS);
+    for progress in &self.progress {
+      headers.extend(progress.period.columns());
+    }
+    headers
+  }
+
+  fn rows(&self) -> Vec<Vec<String>> {
+    let mut row = vec![self.running.to_string()];
+    match &self.time_entry {
+      Some(time_entry) => row.extend(time_entry.row()),
+      None => row.extend(TimeEntryOutput::HEADERS.map(|_| String::new())),
+    }
+    for progress in &self.progress {
+      row.extend(progress.row());
+    }
+    vec![row]
+  }
+}
+
+impl Render for DurationOutput {
+  fn print_human(&self) {
+    match &self.duration {
+      Some(duration) => println!("{}", duration),
+      None => println!("No time entry running."),
+    }
+  }
+
+  fn headers(&self) -> Vec<&str> {
+    vec!["running", "duration"]
+  }
+
+  fn rows(&self) -> Vec<Vec<String>> {
+    vec![vec![
+      self.running.to_string(),
+      self.duration.clone().unwrap_or_default(),
+    ]]
+  }
+}
+
+/// Shows the running time entry, optionally followed by the progress towards
+/// the target hours of the given periods. `template` is `Some(None)` if
+/// `--template` was given without a value, in which case the configured
+/// default is used.
+pub fn status(
+  format: OutputFormat,
+  duration: bool,
+  template: Option<Option<String>>,
+  bar: Option<BarFormat>,
+  watch: Option<u64>,
+  progress: &[ProgressPeriod],
+) -> Result<(), CliError> {
   let config = Config::read()?;
-  let api_client = ApiClient::new(&config.api_user, &config.api_key);
+  let api_client = config.api_client()?;
+  let mut resources = ResourceCache::new(&api_client, &config);
+
+  if template.is_some() || bar.is_some() {
+    let template = match &template {
+      Some(Some(template)) => template,
+      _ => config.status_template(),
+    };
+    return match watch {
+      Some(poll_interval) => watch_status(
+        &api_client,
+        &mut resources,
+        template,
+        bar,
+        Duration::from_secs(poll_interval),
+      ),
+      None => {
+        let entry = api_client.get_status()?.running;
+        ClockState::update(&mut resources, entry.as_ref())?;
+        let line = status_line(&mut resources, template, bar, entry)?;
+        println!("{}", line);
+        Ok(())
+      }
+    };
+  }
 
   let status = api_client.get_status()?;
+  ClockState::update(&mut resources, status.running.as_ref())?;
 
-  let output = JsonOutput {
+  let progress = match progress {
+    [] => Vec::new(),
+    periods => target_progress(&api_client, status.running.as_ref(), periods)?,
+  };
+  let output = StatusOutput {
     running: status.running.is_some(),
+    progress,
     time_entry: match status.running {
       None => None,
       Some(time_entry) => {
-        let customer =
-          api_client.get_resource::<Customer>(time_entry.customers_id)?;
-        let service = match time_entry.services_id {
-          Some(services_id) => {
-            Some(api_client.get_resource::<Service>(services_id)?.name)
-          }
-          None => None,
-        };
-        let project = match time_entry.projects_id {
-          Some(projects_id) => {
-            Some(api_client.get_resource::<Project>(projects_id)?.name)
-          }
-          None => None,
-        };
-
-        Some(TimeEntryOutput::from_time_entry(
-          time_entry,
-          &customer.name,
-          service.as_deref(),
-          project.as_deref(),
-        )?)
+        Some(TimeEntryOutput::resolve(&mut resources, time_entry)?)
       }
     },
   };
 
-  if args.json {
-    if duration {
-      if let Some(time_entry) = &output.time_entry {
-        println!(
-          "{}",
-          json!({
-              "duration": time_entry.duration,
-          })
-          .to_string()
-        );
-      } else {
-        println!(
-          "{}",
-          json!({
-          "running": false,
-          "duration": null,
-          })
-          .to_string()
-        );
-      }
-    } else {
-      println!("{}", serde_json::to_string(&output).unwrap());
+  if duration {
+    DurationOutput {
+      running: output.running,
+      duration: output.time_entry.map(|time_entry| time_entry.duration),
     }
+    .render(format);
   } else {
-    if let Some(time_entry) = output.time_entry {
-      if duration {
-        println!("{}", time_entry.duration);
-        return Ok(());
-      } else {
-        println!("Stopclock is running\n");
-        time_entry.print();
-      }
-    } else {
-      println!("No time entry running.");
+    if format == OutputFormat::Human && output.running {
+      println!("Stopclock is running\n");
     }
+    output.render(format);
   }
 
   Ok(())
 }
+
+/// Sums the durations of the time entries of each period, including the
+/// running one, and compares them against the user's target hours.
+fn target_progress(
+  api_client: &ApiClient,
+  running: Option<&TimeEntry>,
+  periods: &[ProgressPeriod],
+) -> Result<Vec<ProgressOutput>, CliError> {
+  let now = Local::now();
+  let me = api_client.get_current_user()?;
+
+  periods
+    .iter()
+    .map(|&period| {
+      let (since, until) = match period {
+        ProgressPeriod::Day => today_range(),
+        ProgressPeriod::Week => week_range(),
+      };
+      let entries = api_client.list_entries(
+        &to_api_time(since),
+        &to_api_time(until),
+        Some(me.user.id),
+      )?;
+
+      // The running entry is counted separately, as it may be missing from
+      // the list
+      let mut worked = 0;
+      for entry in entries.iter().filter(|entry| {
+        matches!(entry.entry_type, EntryType::TimeEntry)
+          && entry.time_until.is_some()
+          && Some(entry.id) != running.map(|running| running.id)
+      }) {
+        worked += entry.elapsed()?;
+      }
+      if let Some(running) = running {
+        // Only count the part of the running entry within the period
+        let period_elapsed = (Utc::now() - since).num_seconds();
+        worked += running.elapsed()?.min(period_elapsed);
+      }
+
+      let first_day = since.with_timezone(&Local).date_naive();
+      let last_day = until.with_timezone(&Local).date_naive() - Days::new(1);
+      let (_, target) =
+        sum_report_days(api_client, me.user.id, first_day, last_day)?;
+      let remaining = (target - worked).max(0);
+
+      Ok(ProgressOutput {
+        period,
+        worked_seconds: worked,
+        worked: format_duration_hm(worked),
+        target_seconds: target,
+        target: format_duration_hm(target),
+        remaining_seconds: remaining,
+        remaining: format_duration_hm(remaining),
+        clock_out: (remaining > 0).then(|| {
+          (now + chrono::Duration::seconds(remaining))
+            .format(DATE_FORMAT)
+            .to_string()
+        }),
+      })
+    })
+    .collect()
+}
+
+/// Formats the running entry as a single line, either plain or for a status
+/// bar.
+fn status_line(
+  resources: &mut ResourceCache,
+  template: &str,
+  bar: Option<BarFormat>,
+  entry: Option<TimeEntry>,
+) -> Result<String, CliError> {
+  let time_entry = entry
+    .map(|entry| TimeEntryOutput::resolve(resources, entry))
+    .transpose()?;
+  let text = match &time_entry {
+    Some(time_entry) => {
+      render_template(template, |name| time_entry.field(name))?
+    }
+    // Still validate the template, even though it isn't shown
+    None => {
+      render_template(template, |name| {
+        TimeEntryOutput::HEADERS.contains(&name).then(String::new)
+      })?;
+      String::new()
+    }
+  };
+
+  Ok(match bar {
+    Some(bar) => bar.line(&text, time_entry.as_ref()),
+    None => text,
+  })
+}
+
+/// Prints a status line every second. The duration is computed locally from
+/// the last known entry, the API is only polled every `poll_interval`.
+fn watch_status(
+  api_client: &ApiClient,
+  resources: &mut ResourceCache,
+  template: &str,
+  bar: Option<BarFormat>,
+  poll_interval: Duration,
+) -> Result<(), CliError> {
+  let mut entry = api_client.get_status()?.running;
+  ClockState::update(resources, entry.as_ref())?;
+  let mut polled_at = Instant::now();
+  let mut stdout = std::io::stdout();
+
+  loop {
+    if polled_at.elapsed() >= poll_interval {
+      // Keep showing the last known entry if the API is unreachable
+      if let Ok(status) = api_client.get_status() {
+        entry = status.running;
+        ClockState::update(resources, entry.as_ref())?;
+      }
+      polled_at = Instant::now();
+    }
+
+    let line = status_line(resources, template, bar, entry.clone())?;
+    match writeln!(stdout, "{}", line).and_then(|_| stdout.flush()) {
+      Err(err) if err.kind() == ErrorKind::BrokenPipe => return Ok(()),
+      result => result?,
+    }
+
+    thread::sleep(Duration::from_secs(1));
+  }
+}
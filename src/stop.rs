@@ -2,8 +2,10 @@ use serde::Serialize;
 use thiserror::Error;
 
 use crate::{
-  api::{client::ApiClient, Customer, Project, Service},
+  cache::ResourceCache,
   config::Config,
+  output::{OutputFormat, Render},
+  state::ClockState,
   status::TimeEntryOutput,
   CliError,
 };
@@ -14,6 +16,29 @@ struct StopOutput {
   time_entry: Option<TimeEntryOutput>,
 }
 
+impl Render for StopOutput {
+  fn print_human(&self) {
+    if let Some(time_entry) = &self.time_entry {
+      time_entry.print_human();
+    }
+  }
+
+  fn headers(&self) -> Vec<&str> {
+    let mut headers = vec!["stopped"];
+    headers.extend(TimeEntryOutput::HEADERS);
+    headers
+  }
+
+  fn rows(&self) -> Vec<Vec<String>> {
+    let mut row = vec![self.stopped.to_string()];
+    match &self.time_entry {
+      Some(time_entry) => row.extend(time_entry.row()),
+      None => row.extend(TimeEntryOutput::HEADERS.map(|_| String::new())),
+    }
+    vec![row]
+  }
+}
+
 #[derive(Debug, Error)]
 pub enum StopError {
   #[error("The stopclock is not currently running")]
@@ -26,50 +51,28 @@ pub enum StopError {
   NotStopped,
 }
 
-pub fn stop(json: bool) -> Result<(), CliError> {
+pub fn stop(format: OutputFormat) -> Result<(), CliError> {
   let config = Config::read()?;
-  let api_client = ApiClient::new(&config.api_user, &config.api_key);
+  let api_client = config.api_client()?;
+  let mut resources = ResourceCache::new(&api_client, &config);
 
   let entry = api_client
     .stop_clock()?
     .ok_or(StopError::NotRunning)?
     .stopped
     .ok_or(StopError::NotStopped)?;
+  ClockState::save(&config.account_name(), None);
 
-  let customer = api_client
-    .get_resource::<Customer>(entry.customers_id)?
-    .name;
-
-  let project = match entry.projects_id {
-    Some(id) => Some(api_client.get_resource::<Project>(id)?.name),
-    None => None,
-  };
-
-  let service = match entry.services_id {
-    Some(id) => Some(api_client.get_resource::<Service>(id)?.name),
-    None => None,
-  };
+  let entry = TimeEntryOutput::resolve(&mut resources, entry)?;
 
-  let entry = TimeEntryOutput::from_time_entry(
-    entry,
-    &customer,
-    service.as_deref(),
-    project.as_deref(),
-  )?;
-
-  if json {
-    println!(
-      "{}",
-      serde_json::to_string(&StopOutput {
-        stopped: true,
-        time_entry: Some(entry),
-      })
-      .unwrap()
-    );
-  } else {
-    println!("{}", "The stopclock has been stopped.\n");
-    entry.print();
+  if format == OutputFormat::Human {
+    println!("The stopclock has been stopped.\n");
+  }
+  StopOutput {
+    stopped: true,
+    time_entry: Some(entry),
   }
+  .render(format);
 
   Ok(())
 }
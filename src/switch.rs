@@ -0,0 +1,134 @@
+use serde::Serialize;
+
+use crate::{
+  api::{Customer, Project, Service},
+  cache::ResourceCache,
+  config::Config,
+  output::{OutputFormat, Render},
+  start::{find_customer, find_project, find_service, StartError},
+  state::ClockState,
+  status::TimeEntryOutput,
+  stop::StopError,
+  CliError,
+};
+
+#[derive(Debug, Serialize)]
+struct SwitchOutput {
+  stopped: TimeEntryOutput,
+  running: TimeEntryOutput,
+}
+
+impl Render for SwitchOutput {
+  fn print_human(&self) {
+    println!("Stopped the previous time entry.\n");
+    self.stopped.print_human();
+    println!("\nStarted the stopclock.\n");
+    self.running.print_human();
+  }
+
+  fn headers(&self) -> Vec<&str> {
+    let mut headers = vec!["state"];
+    headers.extend(TimeEntryOutput::HEADERS);
+    headers
+  }
+
+  fn rows(&self) -> Vec<Vec<String>> {
+    let row = |state: &str, time_entry: &TimeEntryOutput| {
+      let mut row = vec![state.to_string()];
+      row.extend(time_entry.row());
+      row
+    };
+    vec![row("stopped", &self.stopped), row("running", &self.running)]
+  }
+}
+
+pub fn switch(
+  format: OutputFormat,
+  customer: Option<&str>,
+  service: Option<&str>,
+  project: Option<&str>,
+  billable: Option<bool>,
+  description: Option<&str>,
+) -> Result<(), CliError> {
+  let config = Config::read()?;
+  let api_client = config.api_client()?;
+  let mut resources = ResourceCache::new(&api_client, &config);
+
+  let previous = api_client
+    .get_status()?
+    .running
+    .ok_or(StopError::NotRunning)?;
+
+  // Resolve everything before stopping, so a typo doesn't leave the clock
+  // stopped
+  let customer = match customer {
+    Some(customer) => find_customer(&mut resources, customer)?,
+    None => resources.get::<Customer>(previous.customers_id)?,
+  };
+  let service = match (service, previous.services_id) {
+    (Some(service), _) => find_service(&mut resources, service)?,
+    (None, Some(services_id)) => resources.get::<Service>(services_id)?,
+    (None, None) => return Err(StartError::MissingService.into()),
+  };
+  let project = match (project, previous.projects_id) {
+    (Some(project), _) => {
+      Some(find_project(&mut resources, &customer, project)?)
+    }
+    // The previous project only applies if the customer stays the same
+    (None, Some(projects_id)) if customer.id == previous.customers_id => {
+      Some(resources.get::<Project>(projects_id)?)
+    }
+    (None, _) => None,
+  };
+  let billable = billable.unwrap_or(previous.billable.is_billable());
+
+  let stopped = api_client
+    .stop_clock()?
+    .ok_or(StopError::NotRunning)?
+    .stopped
+    .ok_or(StopError::NotStopped)?;
+
+  let running = api_client
+    .start_clock(
+      customer.id,
+      service.id,
+      Some(billable),
+      project.as_ref().map(|p| p.id),
+      description,
+      stopped.time_until.as_deref(),
+    )
+    .map_err(CliError::from)
+    .and_then(|response| {
+      response
+        .running
+        .ok_or_else(|| StartError::FailedToStart.into())
+    });
+  let running = match running {
+    Ok(running) => running,
+    Err(reason) => {
+      ClockState::save(&config.account_name(), None);
+      return Err(
+        StartError::StartAfterStopFailed {
+          stopped: stopped.id,
+          reason: Box::new(reason),
+        }
+        .into(),
+      );
+    }
+  };
+  ClockState::update(&mut resources, Some(&running))?;
+
+  let output = SwitchOutput {
+    stopped: TimeEntryOutput::resolve(&mut resources, stopped)?,
+    running: TimeEntryOutput::from_time_entry(
+      running,
+      &customer.name,
+      Some(&service.name),
+      project.as_ref().map(|p| p.name.as_str()),
+    )?,
+  };
+
+  output.render(format);
+
+  Ok(())
+}
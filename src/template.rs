@@ -0,0 +1,114 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TemplateError {
+  #[error("Unknown template placeholder {{{0}}}")]
+  UnknownPlaceholder(String),
+
+  #[error("Unclosed template placeholder {{{0}")]
+  UnclosedPlaceholder(String),
+}
+
+/// Replaces `{name}` placeholders in a template with the values returned by
+/// `lookup`. `{{` and `}}` produce literal braces.
+pub fn render_template(
+  template: &str,
+  lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String, TemplateError> {
+  let mut output = String::with_capacity(template.len());
+  let mut chars = template.chars().peekable();
+
+  while let Some(c) = chars.next() {
+    match c {
+      '{' if chars.peek() == Some(&'{') => {
+        chars.next();
+        output.push('{');
+      }
+      '}' if chars.peek() == Some(&'}') => {
+        chars.next();
+        output.push('}');
+      }
+      '{' => {
+        let mut name = String::new();
+        loop {
+          match chars.next() {
+            Some('}') => break,
+            Some(c) => name.push(c),
+            None => return Err(TemplateError::UnclosedPlaceholder(name)),
+          }
+        }
+        let value = lookup(name.trim())
+          .ok_or_else(|| TemplateError::UnknownPlaceholder(name.clone()))?;
+        output.push_str(&value);
+      }
+      c => output.push(c),
+    }
+  }
+
+  Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn lookup(name: &str) -> Option<String> {
+    match name {
+      "customer" => Some("ACME GmbH".to_string()),
+      "duration" => Some("01:23:45".to_string()),
+      "empty" => Some(String::new()),
+      _ => None,
+    }
+  }
+
+  #[test]
+  fn replaces_placeholders() {
+    assert_eq!(
+      render_template("{customer} · {duration}", lookup).unwrap(),
+      "ACME GmbH · 01:23:45"
+    );
+    assert_eq!(render_template("[{empty}]", lookup).unwrap(), "[]");
+  }
+
+  #[test]
+  fn trims_placeholder_names() {
+    assert_eq!(
+      render_template("{ customer }", lookup).unwrap(),
+      "ACME GmbH"
+    );
+  }
+
+  #[test]
+  fn doubled_braces_are_literal() {
+    assert_eq!(
+      render_template("{{customer}}: {customer}", lookup).unwrap(),
+      "{customer}: ACME GmbH"
+    );
+    assert_eq!(render_template("}}{{", lookup).unwrap(), "}{");
+  }
+
+  #[test]
+  fn text_without_placeholders_is_unchanged() {
+    assert_eq!(render_template("", lookup).unwrap(), "");
+    assert_eq!(
+      render_template("plain } text", lookup).unwrap(),
+      "plain } text"
+    );
+  }
+
+  #[test]
+  fn unknown_placeholder() {
+    assert!(matches!(
+      render_template("{customer} {nope}", lookup),
+      Err(TemplateError::UnknownPlaceholder(name)) if name == "nope"
+    ));
+  }
+
+  #[test]
+  fn unclosed_placeholder() {
+    assert!(matches!(
+      render_template("{customer} {dura", lookup),
+      Err(TemplateError::UnclosedPlaceholder(name)) if name == "dura"
+    ));
+  }
+}
@@ -0,0 +1,190 @@
+use chrono::{
+  DateTime, Datelike, Days, Local, Months, NaiveDate, NaiveDateTime, NaiveTime,
+  TimeZone, Utc,
+};
+
+/// Format used to display timestamps.
+pub const DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Format used by the Clockodo API for timestamps.
+const API_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+/// Parses a user-supplied point in time. Accepts RFC 3339 timestamps as well
+/// as local `YYYY-MM-DD HH:MM[:SS]`, `YYYY-MM-DD` (start of day) and
+/// `HH:MM[:SS]` (today).
+pub fn parse_time(input: &str) -> Result<DateTime<Utc>, chrono::ParseError> {
+  let input = input.trim();
+
+  if let Ok(time) = DateTime::parse_from_rfc3339(input) {
+    return Ok(time.with_timezone(&Utc));
+  }
+
+  let naive = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M:%S")
+    .or_else(|_| NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M"))
+    .or_else(|_| {
+      NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .map(|date| date.and_time(NaiveTime::MIN))
+    })
+    .or_else(|_| {
+      NaiveTime::parse_from_str(input, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(input, "%H:%M"))
+        .map(|time| Local::now().date_naive().and_time(time))
+    })?;
+
+  Ok(local_to_utc(naive))
+}
+
+/// Format used by the Clockodo API for dates.
+pub const API_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Parses a user-supplied `YYYY-MM-DD` date.
+pub fn parse_date(input: &str) -> Result<NaiveDate, chrono::ParseError> {
+  NaiveDate::parse_from_str(input.trim(), API_DATE_FORMAT)
+}
+
+/// Interprets a naive date time as local time.
+pub fn local_to_utc(naive: NaiveDateTime) -> DateTime<Utc> {
+  Local
+    .from_local_datetime(&naive)
+    .earliest()
+    .unwrap_or_else(|| Local.from_utc_datetime(&naive))
+    .with_timezone(&Utc)
+}
+
+/// Returns the start of the current local day.
+pub fn start_of_today() -> DateTime<Utc> {
+  local_to_utc(Local::now().date_naive().and_time(NaiveTime::MIN))
+}
+
+fn local_date_range(
+  start: NaiveDate,
+  end: NaiveDate,
+) -> (DateTime<Utc>, DateTime<Utc>) {
+  (
+    local_to_utc(start.and_time(NaiveTime::MIN)),
+    local_to_utc(end.and_time(NaiveTime::MIN)),
+  )
+}
+
+/// Returns the start and (exclusive) end of the current local day.
+pub fn today_range() -> (DateTime<Utc>, DateTime<Utc>) {
+  let today = Local::now().date_naive();
+  local_date_range(today, today + Days::new(1))
+}
+
+/// Returns the start and (exclusive) end of the current week, starting on
+/// Monday.
+pub fn week_range() -> (DateTime<Utc>, DateTime<Utc>) {
+  let today = Local::now().date_naive();
+  let monday = today - Days::new(today.weekday().num_days_from_monday().into());
+  local_date_range(monday, monday + Days::new(7))
+}
+
+/// Returns the start and (exclusive) end of the current month.
+pub fn month_range() -> (DateTime<Utc>, DateTime<Utc>) {
+  let first = Local::now().date_naive().with_day(1).unwrap();
+  local_date_range(first, first + Months::new(1))
+}
+
+/// Formats an API timestamp in local time.
+pub fn format_time(time: &str) -> Result<String, chrono::ParseError> {
+  Ok(
+    time
+      .parse::<DateTime<Utc>>()?
+      .with_timezone(&Local)
+      .format(DATE_FORMAT)
+      .to_string(),
+  )
+}
+
+/// Formats a timestamp the way the Clockodo API expects it.
+pub fn to_api_time(time: DateTime<Utc>) -> String {
+  time.format(API_FORMAT).to_string()
+}
+
+/// Formats a duration in seconds as `HH:MM`.
+pub fn format_duration_hm(duration: i64) -> String {
+  format!("{:0>2}:{:0>2}", duration / 3600, duration % 3600 / 60)
+}
+
+/// Formats a difference in seconds as `+HH:MM` or `-HH:MM`.
+pub fn format_duration_diff(duration: i64) -> String {
+  let sign = if duration < 0 { '-' } else { '+' };
+  format!("{sign}{}", format_duration_hm(duration.abs()))
+}
+
+/// Formats a duration in seconds as `HH:MM:SS`.
+pub fn format_duration(duration: i64) -> String {
+  format!(
+    "{:0>2}:{:0>2}:{:0>2}",
+    duration / 3600,
+    duration % 3600 / 60,
+    duration % 60
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn local(date: &str, time: &str) -> DateTime<Utc> {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+    let time = NaiveTime::parse_from_str(time, "%H:%M:%S").unwrap();
+    local_to_utc(date.and_time(time))
+  }
+
+  #[test]
+  fn parses_rfc3339() {
+    assert_eq!(
+      parse_time("2026-10-18T10:00:00+02:00").unwrap(),
+      Utc.with_ymd_and_hms(2026, 10, 18, 8, 0, 0).unwrap()
+    );
+    assert_eq!(
+      parse_time("2026-10-18T08:00:00Z").unwrap(),
+      Utc.with_ymd_and_hms(2026, 10, 18, 8, 0, 0).unwrap()
+    );
+  }
+
+  #[test]
+  fn parses_local_date_time() {
+    assert_eq!(
+      parse_time("2026-10-18 10:30:15").unwrap(),
+      local("2026-10-18", "10:30:15")
+    );
+    assert_eq!(
+      parse_time("2026-10-18 10:30").unwrap(),
+      local("2026-10-18", "10:30:00")
+    );
+  }
+
+  #[test]
+  fn parses_date_as_start_of_day() {
+    assert_eq!(
+      parse_time("2026-10-18").unwrap(),
+      local("2026-10-18", "00:00:00")
+    );
+  }
+
+  #[test]
+  fn parses_time_as_today() {
+    let today = Local::now().date_naive().format("%Y-%m-%d").to_string();
+    assert_eq!(parse_time("09:15").unwrap(), local(&today, "09:15:00"));
+    assert_eq!(parse_time("09:15:30").unwrap(), local(&today, "09:15:30"));
+  }
+
+  #[test]
+  fn trims_input() {
+    assert_eq!(
+      parse_time("  2026-10-18 10:30\n").unwrap(),
+      local("2026-10-18", "10:30:00")
+    );
+  }
+
+  #[test]
+  fn rejects_invalid_input() {
+    assert!(parse_time("").is_err());
+    assert!(parse_time("tomorrow").is_err());
+    assert!(parse_time("2026-13-01").is_err());
+    assert!(parse_time("25:00").is_err());
+  }
+}